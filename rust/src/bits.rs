@@ -0,0 +1,360 @@
+// BITS packet format (2021 day 16): decoder from hex with proper errors, encoder back to hex,
+// S-expression pretty printer and evaluator.
+
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+const LITERAL_TYPE: u64 = 4;
+
+impl Operator {
+    fn from_type_id(type_id: u64) -> Result<Operator, BitsError> {
+        match type_id {
+            0 => Ok(Operator::Sum),
+            1 => Ok(Operator::Product),
+            2 => Ok(Operator::Minimum),
+            3 => Ok(Operator::Maximum),
+            5 => Ok(Operator::GreaterThan),
+            6 => Ok(Operator::LessThan),
+            7 => Ok(Operator::EqualTo),
+            _ => Err(BitsError::InvalidType(type_id)),
+        }
+    }
+
+    fn type_id(&self) -> u64 {
+        match self {
+            Operator::Sum => 0,
+            Operator::Product => 1,
+            Operator::Minimum => 2,
+            Operator::Maximum => 3,
+            Operator::GreaterThan => 5,
+            Operator::LessThan => 6,
+            Operator::EqualTo => 7,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Operator::Sum => "sum",
+            Operator::Product => "product",
+            Operator::Minimum => "min",
+            Operator::Maximum => "max",
+            Operator::GreaterThan => ">",
+            Operator::LessThan => "<",
+            Operator::EqualTo => "=",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Payload {
+    Literal(u64),
+    Operator(Operator, Vec<Packet>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    pub version: u8,
+    pub payload: Payload,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BitsError {
+    /// character at the given position is not an hex digit
+    InvalidHex(usize, char),
+    /// needed more bits than available, at the given bit position
+    Truncated(usize),
+    /// operator type id out of range (only 4 is literal)
+    InvalidType(u64),
+    /// literal value does not fit in 64 bits
+    LiteralOverflow,
+    /// sub-packets went past the declared bit length
+    LengthMismatch { declared: usize, read: usize },
+    /// non-zero bits left after the outermost packet
+    TrailingData(usize),
+    /// operator applied to the wrong number of operands
+    Operands(Operator, usize),
+    /// evaluation overflowed
+    Overflow,
+}
+
+impl Display for BitsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BitsError::InvalidHex(pos, ch) => write!(f, "invalid hex digit '{ch}' at {pos}"),
+            BitsError::Truncated(pos) => write!(f, "truncated packet at bit {pos}"),
+            BitsError::InvalidType(id) => write!(f, "invalid packet type {id}"),
+            BitsError::LiteralOverflow => write!(f, "literal does not fit 64 bits"),
+            BitsError::LengthMismatch { declared, read } =>
+                write!(f, "sub-packets length {read} does not match declared {declared}"),
+            BitsError::TrailingData(pos) => write!(f, "unexpected data after bit {pos}"),
+            BitsError::Operands(op, count) => write!(f, "{} with {count} operands", op.name()),
+            BitsError::Overflow => write!(f, "evaluation overflow"),
+        }
+    }
+}
+
+/// decodes a full transmission (one outermost packet, zero padded) from its hex representation
+pub fn decode(hex: &str) -> Result<Packet, BitsError> {
+    let mut reader = BitReader::from_hex(hex.trim())?;
+    let packet = reader.packet()?;
+    if reader.bits[reader.pos..].iter().any(|b| *b) {
+        return Err(BitsError::TrailingData(reader.pos));
+    }
+    Ok(packet)
+}
+
+/// encodes a packet to hex, padded with zeros to the next hex digit.
+pub fn encode(packet: &Packet) -> String {
+    let mut writer = BitWriter { bits: Vec::new() };
+    writer.packet(packet);
+    writer.to_hex()
+}
+
+impl Packet {
+    pub fn literal(version: u8, value: u64) -> Packet {
+        Packet { version, payload: Payload::Literal(value) }
+    }
+
+    pub fn operator(version: u8, op: Operator, packets: Vec<Packet>) -> Packet {
+        Packet { version, payload: Payload::Operator(op, packets) }
+    }
+
+    /// sum of the versions of this packet and all the sub-packets
+    pub fn version_sum(&self) -> u64 {
+        let sub: u64 = match &self.payload {
+            Payload::Literal(_) => 0,
+            Payload::Operator(_, packets) => packets.iter().map(|p| p.version_sum()).sum(),
+        };
+        self.version as u64 + sub
+    }
+
+    pub fn eval(&self) -> Result<u64, BitsError> {
+        let (op, packets) = match &self.payload {
+            Payload::Literal(value) => return Ok(*value),
+            Payload::Operator(op, packets) => (op, packets),
+        };
+        let values = packets.iter().map(|p| p.eval()).collect::<Result<Vec<u64>, BitsError>>()?;
+        let comparison = |cmp: fn(&u64, &u64) -> bool| {
+            if values.len() != 2 {
+                return Err(BitsError::Operands(*op, values.len()));
+            }
+            Ok(if cmp(&values[0], &values[1]) { 1 } else { 0 })
+        };
+        match op {
+            Operator::Sum => values.iter().try_fold(0_u64, |acc, v| acc.checked_add(*v)).ok_or(BitsError::Overflow),
+            Operator::Product => values.iter().try_fold(1_u64, |acc, v| acc.checked_mul(*v)).ok_or(BitsError::Overflow),
+            Operator::Minimum => values.iter().min().copied().ok_or(BitsError::Operands(*op, 0)),
+            Operator::Maximum => values.iter().max().copied().ok_or(BitsError::Operands(*op, 0)),
+            Operator::GreaterThan => comparison(u64::gt),
+            Operator::LessThan => comparison(u64::lt),
+            Operator::EqualTo => comparison(u64::eq),
+        }
+    }
+
+    /// multi-line S-expression, with versions, one packet per line
+    pub fn pretty(&self) -> String {
+        let mut text = String::new();
+        self.pretty_indent(&mut text, 0);
+        text
+    }
+
+    fn pretty_indent(&self, text: &mut String, indent: usize) {
+        text.push_str(&" ".repeat(indent));
+        match &self.payload {
+            Payload::Literal(value) => text.push_str(&format!("v{}:{value}", self.version)),
+            Payload::Operator(op, packets) => {
+                text.push_str(&format!("(v{}:{}", self.version, op.name()));
+                for p in packets {
+                    text.push('\n');
+                    p.pretty_indent(text, indent + 2);
+                }
+                text.push(')');
+            }
+        }
+    }
+}
+
+/// compact S-expression, e.g. `(sum 1 (product 2 3))` - versions are not shown
+impl Display for Packet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.payload {
+            Payload::Literal(value) => write!(f, "{value}"),
+            Payload::Operator(op, packets) => {
+                write!(f, "({}", op.name())?;
+                for p in packets {
+                    write!(f, " {p}")?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+struct BitReader {
+    pos: usize,
+    bits: Vec<bool>,
+}
+
+impl BitReader {
+    fn from_hex(text: &str) -> Result<BitReader, BitsError> {
+        let mut bits = Vec::with_capacity(text.len() * 4);
+        for (i, ch) in text.chars().enumerate() {
+            let Some(digit) = ch.to_digit(16) else {
+                return Err(BitsError::InvalidHex(i, ch));
+            };
+            (0..4).rev().for_each(|b| bits.push(digit & (1 << b) != 0));
+        }
+        Ok(BitReader { pos: 0, bits })
+    }
+
+    fn read(&mut self, count: usize) -> Result<u64, BitsError> {
+        if self.pos + count > self.bits.len() {
+            return Err(BitsError::Truncated(self.pos));
+        }
+        let val = self.bits[self.pos..self.pos + count].iter()
+            .fold(0, |acc, b| (acc << 1) | *b as u64);
+        self.pos += count;
+        Ok(val)
+    }
+
+    fn packet(&mut self) -> Result<Packet, BitsError> {
+        let version = self.read(3)? as u8;
+        let type_id = self.read(3)?;
+        if type_id == LITERAL_TYPE {
+            return Ok(Packet::literal(version, self.literal()?));
+        }
+        let op = Operator::from_type_id(type_id)?;
+        let mut packets = Vec::new();
+        if self.read(1)? == 1 {
+            let count = self.read(11)?;
+            for _ in 0..count {
+                packets.push(self.packet()?);
+            }
+        } else {
+            let length = self.read(15)? as usize;
+            let start = self.pos;
+            while self.pos - start < length {
+                packets.push(self.packet()?);
+            }
+            if self.pos - start != length {
+                return Err(BitsError::LengthMismatch { declared: length, read: self.pos - start });
+            }
+        }
+        Ok(Packet::operator(version, op, packets))
+    }
+
+    fn literal(&mut self) -> Result<u64, BitsError> {
+        let mut value: u64 = 0;
+        loop {
+            let more = self.read(1)? == 1;
+            if value.leading_zeros() < 4 {
+                return Err(BitsError::LiteralOverflow);
+            }
+            value = (value << 4) | self.read(4)?;
+            if !more {
+                return Ok(value);
+            }
+        }
+    }
+}
+
+struct BitWriter {
+    bits: Vec<bool>,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u64, count: usize) {
+        (0..count).rev().for_each(|b| self.bits.push(value & (1 << b) != 0));
+    }
+
+    fn packet(&mut self, packet: &Packet) {
+        self.write(packet.version as u64, 3);
+        match &packet.payload {
+            Payload::Literal(value) => {
+                self.write(LITERAL_TYPE, 3);
+                let groups = (64 - value.leading_zeros() as usize).div_ceil(4);
+                for g in (0..groups.max(1)).rev() {
+                    self.write(if g > 0 { 1 } else { 0 }, 1);
+                    self.write(value >> (g * 4), 4);
+                }
+            }
+            Payload::Operator(op, packets) => {
+                self.write(op.type_id(), 3);
+                if packets.len() < (1 << 11) {
+                    self.write(1, 1);
+                    self.write(packets.len() as u64, 11);
+                    packets.iter().for_each(|p| self.packet(p));
+                } else {
+                    // too many packets for the count: encode sub packets and write their length
+                    let mut sub = BitWriter { bits: Vec::new() };
+                    packets.iter().for_each(|p| sub.packet(p));
+                    assert!(sub.bits.len() < (1 << 15), "Too many sub-packets to encode");
+                    self.write(0, 1);
+                    self.write(sub.bits.len() as u64, 15);
+                    self.bits.extend(sub.bits);
+                }
+            }
+        }
+    }
+
+    fn to_hex(&self) -> String {
+        self.bits.chunks(4)
+            .map(|chunk| {
+                let digit = (0..4).fold(0, |acc, i| (acc << 1) | *chunk.get(i).unwrap_or(&false) as u32);
+                char::from_digit(digit, 16).unwrap().to_ascii_uppercase()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bits::{decode, encode, BitsError, Operator, Packet};
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode("D2FE28"), Ok(Packet::literal(6, 2021)));
+        let packet = decode("38006F45291200").unwrap();
+        assert_eq!(packet, Packet::operator(1, Operator::LessThan,
+                                            vec![Packet::literal(6, 10), Packet::literal(2, 20)]));
+        assert_eq!(decode("EE00D40C823060").unwrap().to_string(), "(max 1 2 3)");
+        assert_eq!(decode("A0016C880162017C3686B18A3D4780").unwrap().version_sum(), 31);
+    }
+
+    #[test]
+    fn test_eval() {
+        let data = [("C200B40A82", 3), ("04005AC33890", 54), ("880086C3E88112", 7), ("CE00C43D881120", 9),
+            ("D8005AC2A8F0", 1), ("F600BC2D8F", 0), ("9C005AC2F8F0", 0), ("9C0141080250320F1802104A08", 1)];
+        for (hex, result) in data {
+            assert_eq!(decode(hex).and_then(|p| p.eval()), Ok(result), "{hex}");
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(decode("D2FE2"), Err(BitsError::Truncated(17)));
+        assert_eq!(decode("D2XE28"), Err(BitsError::InvalidHex(2, 'X')));
+        assert_eq!(decode("D2FE28F"), Err(BitsError::TrailingData(21)));
+        let compare = Packet::operator(0, Operator::EqualTo, vec![Packet::literal(0, 1)]);
+        assert_eq!(compare.eval(), Err(BitsError::Operands(Operator::EqualTo, 1)));
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(&Packet::literal(6, 2021)), "D2FE28");
+        let packet = decode("9C0141080250320F1802104A08").unwrap();
+        assert_eq!(decode(&encode(&packet)), Ok(packet.clone()));
+        assert_eq!(packet.to_string(), "(= (sum 1 3) (product 2 2))");
+        assert_eq!(packet.pretty(), "(v4:=\n  (v2:sum\n    v2:1\n    v4:3)\n  (v6:product\n    v0:2\n    v2:2))");
+    }
+}
@@ -0,0 +1,202 @@
+// Exact arithmetic expression trees with (at most) one unknown: evaluation over big rationals,
+// detection of where the unknown is, and solving of linear equations `lhs = rhs`.
+
+use std::fmt::{Display, Formatter};
+
+use num::{BigInt, BigRational, One, Zero};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Op {
+    fn apply(&self, left: &BigRational, right: &BigRational) -> Result<BigRational, ExprError> {
+        Ok(match self {
+            Op::Add => left + right,
+            Op::Sub => left - right,
+            Op::Mul => left * right,
+            Op::Div => {
+                if right.is_zero() {
+                    return Err(ExprError::DivisionByZero);
+                }
+                left / right
+            }
+        })
+    }
+}
+
+impl Display for Op {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Op::Add => "+",
+            Op::Sub => "-",
+            Op::Mul => "*",
+            Op::Div => "/",
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprError {
+    /// the expression can't be evaluated as it contains the unknown
+    Unknown,
+    DivisionByZero,
+    /// the unknown appears in a non-linear way (e.g. x * x or 1 / x)
+    NonLinear,
+    /// the equation has no unknown, or it cancels out
+    NoSolution,
+}
+
+impl Display for ExprError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExprError::Unknown => write!(f, "expression contains the unknown"),
+            ExprError::DivisionByZero => write!(f, "division by zero"),
+            ExprError::NonLinear => write!(f, "equation is not linear in the unknown"),
+            ExprError::NoSolution => write!(f, "equation has no unique solution"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Const(BigRational),
+    Unknown,
+    Binary(Box<Expr>, Op, Box<Expr>),
+}
+
+/// a * x + b
+struct Linear {
+    a: BigRational,
+    b: BigRational,
+}
+
+impl Expr {
+    pub fn num(val: i64) -> Expr {
+        Expr::Const(BigRational::from_integer(BigInt::from(val)))
+    }
+
+    pub fn binary(left: Expr, op: Op, right: Expr) -> Expr {
+        Expr::Binary(Box::new(left), op, Box::new(right))
+    }
+
+    pub fn contains_unknown(&self) -> bool {
+        match self {
+            Expr::Const(_) => false,
+            Expr::Unknown => true,
+            Expr::Binary(left, _, right) => left.contains_unknown() || right.contains_unknown(),
+        }
+    }
+
+    /// exact value of the expression, if it doesn't contain the unknown
+    pub fn eval(&self) -> Result<BigRational, ExprError> {
+        match self {
+            Expr::Const(val) => Ok(val.clone()),
+            Expr::Unknown => Err(ExprError::Unknown),
+            Expr::Binary(left, op, right) => op.apply(&left.eval()?, &right.eval()?),
+        }
+    }
+
+    fn linear(&self) -> Result<Linear, ExprError> {
+        match self {
+            Expr::Const(val) => Ok(Linear { a: BigRational::zero(), b: val.clone() }),
+            Expr::Unknown => Ok(Linear { a: BigRational::one(), b: BigRational::zero() }),
+            Expr::Binary(left, op, right) => {
+                let l = left.linear()?;
+                let r = right.linear()?;
+                match op {
+                    Op::Add => Ok(Linear { a: l.a + r.a, b: l.b + r.b }),
+                    Op::Sub => Ok(Linear { a: l.a - r.a, b: l.b - r.b }),
+                    Op::Mul => {
+                        if !l.a.is_zero() && !r.a.is_zero() {
+                            return Err(ExprError::NonLinear);
+                        }
+                        Ok(Linear { a: &l.a * &r.b + &r.a * &l.b, b: l.b * r.b })
+                    }
+                    Op::Div => {
+                        if !r.a.is_zero() {
+                            return Err(ExprError::NonLinear);
+                        }
+                        if r.b.is_zero() {
+                            return Err(ExprError::DivisionByZero);
+                        }
+                        Ok(Linear { a: l.a / &r.b, b: l.b / r.b })
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Const(val) => write!(f, "{val}"),
+            Expr::Unknown => write!(f, "x"),
+            Expr::Binary(left, op, right) => write!(f, "({left} {op} {right})"),
+        }
+    }
+}
+
+/// solves `lhs = rhs` for the unknown, exactly.
+pub fn solve(lhs: &Expr, rhs: &Expr) -> Result<BigRational, ExprError> {
+    let l = lhs.linear()?;
+    let r = rhs.linear()?;
+    // (l.a - r.a) x = r.b - l.b
+    let a = l.a - r.a;
+    if a.is_zero() {
+        return Err(ExprError::NoSolution);
+    }
+    Ok((r.b - l.b) / a)
+}
+
+#[cfg(test)]
+mod test {
+    use num::{BigInt, BigRational};
+    use crate::expr::{solve, Expr, ExprError, Op};
+
+    fn rational(n: i64, d: i64) -> BigRational {
+        BigRational::new(BigInt::from(n), BigInt::from(d))
+    }
+
+    #[test]
+    fn test_eval() {
+        let expr = Expr::binary(Expr::num(1), Op::Div, Expr::num(3));
+        assert_eq!(expr.eval(), Ok(rational(1, 3)));
+        let expr = Expr::binary(expr, Op::Mul, Expr::num(3));
+        assert_eq!(expr.eval(), Ok(rational(1, 1)));
+        assert_eq!(Expr::binary(Expr::num(1), Op::Div, Expr::num(0)).eval(), Err(ExprError::DivisionByZero));
+        assert_eq!(Expr::binary(Expr::Unknown, Op::Add, Expr::num(0)).eval(), Err(ExprError::Unknown));
+    }
+
+    #[test]
+    fn test_solve() {
+        // (4 + 2 * (x - 3)) / 4 = 150 => x = 301 (2022 day 21 example)
+        let lhs = Expr::binary(
+            Expr::binary(Expr::num(4), Op::Add,
+                         Expr::binary(Expr::num(2), Op::Mul, Expr::binary(Expr::Unknown, Op::Sub, Expr::num(3)))),
+            Op::Div, Expr::num(4));
+        assert!(lhs.contains_unknown());
+        assert_eq!(solve(&lhs, &Expr::num(150)), Ok(rational(301, 1)));
+        assert_eq!(solve(&Expr::num(150), &lhs), Ok(rational(301, 1)));
+        // 3 * x = 1 => 1/3
+        let lhs = Expr::binary(Expr::num(3), Op::Mul, Expr::Unknown);
+        assert_eq!(solve(&lhs, &Expr::num(1)), Ok(rational(1, 3)));
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        let square = Expr::binary(Expr::Unknown, Op::Mul, Expr::Unknown);
+        assert_eq!(solve(&square, &Expr::num(4)), Err(ExprError::NonLinear));
+        let inverse = Expr::binary(Expr::num(1), Op::Div, Expr::Unknown);
+        assert_eq!(solve(&inverse, &Expr::num(4)), Err(ExprError::NonLinear));
+        let cancelled = Expr::binary(Expr::Unknown, Op::Sub, Expr::Unknown);
+        assert_eq!(solve(&cancelled, &Expr::num(4)), Err(ExprError::NoSolution));
+        assert_eq!(solve(&Expr::num(1), &Expr::num(4)), Err(ExprError::NoSolution));
+    }
+}
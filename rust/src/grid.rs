@@ -62,9 +62,39 @@ impl Display for GridPos {
     }
 }
 
+/// Connected-component labelling of a grid stored as a linear array of the given width: orthogonally
+/// adjacent cells with equal values get the same label. Returns the label of each cell (labels are
+/// 0..count, in order of first appearance) and the number of regions.
+pub fn label_regions<T: PartialEq>(cells: &[T], width: usize) -> (Vec<usize>, usize) {
+    let height = cells.len() / width;
+    let mut labels = vec![usize::MAX; cells.len()];
+    let mut count = 0;
+    for first in 0..cells.len() {
+        if labels[first] != usize::MAX {
+            continue;
+        }
+        // flood fill
+        labels[first] = count;
+        let mut stack = vec![first];
+        while let Some(idx) = stack.pop() {
+            let pos = GridPos::from_linear(idx, width);
+            for dir in &ALL_ORTHOGONAL {
+                if let Some(next) = pos.add(dir).to_linear(width, height) {
+                    if labels[next] == usize::MAX && cells[next] == cells[first] {
+                        labels[next] = count;
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+        count += 1;
+    }
+    (labels, count)
+}
+
 #[cfg(test)]
 mod test {
-    use crate::grid::GridPos;
+    use crate::grid::{label_regions, GridPos};
 
     #[test]
     fn test_from_linear() {
@@ -76,4 +106,14 @@ mod test {
             assert_eq!(result.col, coord.1);
         });
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_label_regions() {
+        // AAB
+        // ABB
+        // CAB
+        let (labels, count) = label_regions(&"AABABBCAB".chars().collect::<Vec<_>>(), 3);
+        assert_eq!(count, 4);
+        assert_eq!(labels, vec![0, 0, 1, 0, 1, 1, 2, 3, 1]);
+    }
+}
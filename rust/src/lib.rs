@@ -2,7 +2,14 @@ use std::collections::HashMap;
 
 use once_cell::sync::Lazy;
 
+pub mod bits;
+pub mod expr;
 pub mod grid;
+pub mod linalg;
+pub mod math;
+pub mod memo;
+pub mod orderlist;
+pub mod sequence;
 pub mod utils;
 
 /// A solver for a AOC puzzle
@@ -0,0 +1,208 @@
+// Integer matrices for linear recurrences over count vectors (population puzzles): a transition
+// table becomes a matrix M such that counts(t + 1) = M * counts(t), and M^n jumps n steps ahead
+// with O(log n) multiplications. An optional modulus keeps the numbers bounded for huge n.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+use std::ops::Mul;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<u64>,
+    /// when set, all the entries are kept modulo this value
+    modulus: Option<u64>,
+}
+
+impl Matrix {
+    pub fn zeros(rows: usize, cols: usize) -> Matrix {
+        Matrix { rows, cols, data: vec![0; rows * cols], modulus: None }
+    }
+
+    pub fn identity(size: usize) -> Matrix {
+        let mut matrix = Matrix::zeros(size, size);
+        (0..size).for_each(|i| matrix.set(i, i, 1));
+        matrix
+    }
+
+    pub fn from_rows(rows: &[Vec<u64>]) -> Matrix {
+        let cols = rows.first().map_or(0, |r| r.len());
+        assert!(rows.iter().all(|r| r.len() == cols), "Rows of different length");
+        Matrix { rows: rows.len(), cols, data: rows.concat(), modulus: None }
+    }
+
+    /// same matrix, with all the arithmetic done modulo `modulus` from now on
+    pub fn with_modulus(mut self, modulus: u64) -> Matrix {
+        assert!(modulus > 0, "Modulus must be positive");
+        self.data.iter_mut().for_each(|v| *v %= modulus);
+        self.modulus = Some(modulus);
+        self
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> u64 {
+        self.data[row * self.cols + col]
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, val: u64) {
+        self.data[row * self.cols + col] = self.reduce(val as u128);
+    }
+
+    fn reduce(&self, val: u128) -> u64 {
+        match self.modulus {
+            Some(m) => (val % m as u128) as u64,
+            None => u64::try_from(val).expect("Matrix entry overflow: use a modulus"),
+        }
+    }
+
+    /// self^exp, by repeated squaring
+    pub fn pow(&self, mut exp: u64) -> Matrix {
+        assert_eq!(self.rows, self.cols, "Only square matrices have powers");
+        let mut result = Matrix { modulus: self.modulus, ..Matrix::identity(self.rows) };
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    /// self * vector (as a column)
+    pub fn apply(&self, vector: &[u64]) -> Vec<u64> {
+        assert_eq!(self.cols, vector.len(), "Vector size doesn't match the matrix");
+        (0..self.rows)
+            .map(|r| self.reduce((0..self.cols).map(|c| self.get(r, c) as u128 * vector[c] as u128).sum()))
+            .collect()
+    }
+}
+
+impl Mul for &Matrix {
+    type Output = Matrix;
+
+    fn mul(self, rhs: &Matrix) -> Matrix {
+        assert_eq!(self.cols, rhs.rows, "Matrix sizes don't match");
+        let mut result = Matrix { modulus: self.modulus.or(rhs.modulus), ..Matrix::zeros(self.rows, rhs.cols) };
+        for r in 0..self.rows {
+            for c in 0..rhs.cols {
+                let sum: u128 = (0..self.cols).map(|k| self.get(r, k) as u128 * rhs.get(k, c) as u128).sum();
+                result.set(r, c, result.reduce(sum));
+            }
+        }
+        result
+    }
+}
+
+impl Display for Matrix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for r in 0..self.rows {
+            let row: Vec<String> = (0..self.cols).map(|c| self.get(r, c).to_string()).collect();
+            writeln!(f, "[{}]", row.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Builds the transition matrix of a population from rules like "every X becomes a Y and a Z":
+/// states are given an index in order of appearance.
+pub struct Transitions<K> {
+    states: Vec<K>,
+    index: HashMap<K, usize>,
+    /// (from, to, count)
+    rules: Vec<(usize, usize, u64)>,
+}
+
+impl<K: Clone + Eq + Hash> Default for Transitions<K> {
+    fn default() -> Self {
+        Transitions::new()
+    }
+}
+
+impl<K: Clone + Eq + Hash> Transitions<K> {
+    pub fn new() -> Transitions<K> {
+        Transitions { states: Vec::new(), index: HashMap::new(), rules: Vec::new() }
+    }
+
+    /// index of a state, added if new
+    pub fn state(&mut self, state: &K) -> usize {
+        if let Some(idx) = self.index.get(state) {
+            return *idx;
+        }
+        self.states.push(state.clone());
+        self.index.insert(state.clone(), self.states.len() - 1);
+        self.states.len() - 1
+    }
+
+    /// at every step, each element in state `from` produces `count` elements in state `to`
+    pub fn add(&mut self, from: &K, to: &K, count: u64) -> &mut Self {
+        let (from, to) = (self.state(from), self.state(to));
+        self.rules.push((from, to, count));
+        self
+    }
+
+    pub fn states(&self) -> &[K] {
+        &self.states
+    }
+
+    pub fn matrix(&self) -> Matrix {
+        let mut matrix = Matrix::zeros(self.states.len(), self.states.len());
+        for (from, to, count) in &self.rules {
+            let val = matrix.get(*to, *from) + count;
+            matrix.set(*to, *from, val);
+        }
+        matrix
+    }
+
+    /// count vector for the matrix, from (state, count) pairs; unknown states are not allowed
+    pub fn vector<'a>(&self, counts: impl IntoIterator<Item = (&'a K, u64)>) -> Vec<u64> where K: 'a {
+        let mut vector = vec![0; self.states.len()];
+        for (state, count) in counts {
+            vector[self.index[state]] += count;
+        }
+        vector
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::linalg::{Matrix, Transitions};
+
+    #[test]
+    fn test_pow() {
+        let fib = Matrix::from_rows(&[vec![1, 1], vec![1, 0]]);
+        assert_eq!(fib.pow(0), Matrix::identity(2));
+        assert_eq!(fib.pow(10).get(0, 1), 55);
+        assert_eq!(fib.pow(90).get(0, 1), 2880067194370816120);
+        // fib(10^12) mod 10^9 + 7
+        let fib = fib.with_modulus(1_000_000_007);
+        assert_eq!(fib.pow(1_000_000_000_000).get(0, 1), 730_695_249);
+        assert_eq!(fib.pow(10).apply(&[1, 0]), vec![89, 55]);
+    }
+
+    #[test]
+    fn test_transitions() {
+        // lanternfish (2021 day 6): each timer goes down by one, 0 resets to 6 and spawns an 8
+        let mut fish = Transitions::new();
+        (0..=8).for_each(|t| { fish.state(&t); });
+        (1..=8).for_each(|t| { fish.add(&t, &(t - 1), 1); });
+        fish.add(&0, &6, 1).add(&0, &8, 1);
+        let start = fish.vector([(&3, 2), (&4, 1), (&1, 1), (&2, 1)]);
+        let sum = |days| fish.matrix().pow(days).apply(&start).iter().sum::<u64>();
+        assert_eq!(sum(18), 26);
+        assert_eq!(sum(80), 5934);
+        assert_eq!(sum(256), 26984457539);
+    }
+}
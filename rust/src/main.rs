@@ -13,11 +13,13 @@ use std::time::SystemTime;
 mod year2021;
 mod year2022;
 mod year2023;
+mod year2024;
 
 fn register_all_solvers() {
     year2021::register_solvers();
     year2022::register_solvers();
     year2023::register_solvers();
+    year2024::register_solvers();
 }
 
 /// Solves Advent of Code puzzles
@@ -0,0 +1,261 @@
+// Number theory helpers: gcd/lcm, extended Euclid, Chinese Remainder Theorem, modular arithmetic.
+// All functions work on i64, with i128 intermediates where products could overflow.
+
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// greatest common divisor (always >= 0)
+pub fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// least common multiple (always >= 0, 0 if any of the two is 0)
+pub fn lcm(a: i64, b: i64) -> i64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    (a / gcd(a, b) * b).abs()
+}
+
+/// gcd of all values (0 for an empty sequence)
+pub fn gcd_all<I: IntoIterator<Item = i64>>(values: I) -> i64 {
+    values.into_iter().fold(0, gcd)
+}
+
+/// lcm of all values (1 for an empty sequence)
+pub fn lcm_all<I: IntoIterator<Item = i64>>(values: I) -> i64 {
+    values.into_iter().fold(1, lcm)
+}
+
+/// extended Euclid: returns (g, x, y) such that a*x + b*y = g = gcd(a, b)
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// mathematical modulo: the result is always within 0..m (m > 0), unlike `%` which is the
+/// remainder and keeps the sign of the dividend.
+pub fn modulo(val: i64, m: i64) -> i64 {
+    // careful: -7_i64.rem_euclid(4) parses as -(7.rem_euclid(4)), hence (-7_i64).rem_euclid(4)
+    val.rem_euclid(m)
+}
+
+/// modular inverse of a (mod m), if a and m are coprime
+pub fn mod_inv(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = extended_gcd(modulo(a, m), m);
+    if g != 1 {
+        return None;
+    }
+    Some(modulo(x, m))
+}
+
+/// base^exp (mod m) by repeated squaring
+pub fn mod_pow(base: i64, mut exp: u64, m: i64) -> i64 {
+    let m = m as i128;
+    let mut base = (base as i128).rem_euclid(m);
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result as i64
+}
+
+/// Chinese Remainder Theorem: given a list of (residue, modulus) finds x such that
+/// x = residue (mod modulus) for all of them. Moduli don't need to be coprime.
+/// Returns (x, lcm of the moduli) with x in 0..lcm, or None if the congruences are incompatible.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut result = (0_i64, 1_i64);
+    for &(r2, m2) in congruences {
+        let (r1, m1) = result;
+        let r2 = modulo(r2, m2);
+        // x = r1 + m1 * k, need m1 * k = r2 - r1 (mod m2)
+        let (g, p, _) = extended_gcd(m1, m2);
+        if (r2 - r1) % g != 0 {
+            return None;
+        }
+        let m2g = m2 / g;
+        let k = ((r2 - r1) / g) as i128 * p as i128 % m2g as i128;
+        let lcm = m1 as i128 * m2g as i128;
+        let x = (r1 as i128 + m1 as i128 * k).rem_euclid(lcm);
+        result = (x as i64, lcm as i64);
+    }
+    Some(result)
+}
+
+/// An integer modulo M, always kept within 0..M
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct Mod<const M: i64>(i64);
+
+impl<const M: i64> Mod<M> {
+    pub fn new(val: i64) -> Mod<M> {
+        Mod(modulo(val, M))
+    }
+
+    pub fn value(&self) -> i64 {
+        self.0
+    }
+
+    pub fn pow(&self, exp: u64) -> Mod<M> {
+        Mod(mod_pow(self.0, exp, M))
+    }
+
+    /// multiplicative inverse, if the value is coprime with M
+    pub fn inv(&self) -> Option<Mod<M>> {
+        mod_inv(self.0, M).map(Mod)
+    }
+}
+
+impl<const M: i64> From<i64> for Mod<M> {
+    fn from(val: i64) -> Self {
+        Mod::new(val)
+    }
+}
+
+impl<const M: i64> Display for Mod<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<const M: i64> Add for Mod<M> {
+    type Output = Mod<M>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Mod(((self.0 as i128 + rhs.0 as i128) % M as i128) as i64)
+    }
+}
+
+impl<const M: i64> Sub for Mod<M> {
+    type Output = Mod<M>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Mod((self.0 as i128 - rhs.0 as i128).rem_euclid(M as i128) as i64)
+    }
+}
+
+impl<const M: i64> Mul for Mod<M> {
+    type Output = Mod<M>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Mod((self.0 as i128 * rhs.0 as i128 % M as i128) as i64)
+    }
+}
+
+impl<const M: i64> Neg for Mod<M> {
+    type Output = Mod<M>;
+
+    fn neg(self) -> Self::Output {
+        Mod::new(-self.0)
+    }
+}
+
+impl<const M: i64> AddAssign for Mod<M> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const M: i64> SubAssign for Mod<M> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const M: i64> MulAssign for Mod<M> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::{crt, extended_gcd, gcd_all, lcm_all, mod_inv, mod_pow, modulo, Mod};
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd_all([12, -18, 30]), 6);
+        assert_eq!(gcd_all([]), 0);
+        assert_eq!(lcm_all([2, 3, 4]), 12);
+        assert_eq!(lcm_all([23, 19, 13, 17]), 96577);
+        assert_eq!(lcm_all([]), 1);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for (a, b) in [(240, 46), (46, 240), (-7, 3), (17, 0)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(a * x + b * y, g);
+            assert_eq!(g, gcd_all([a, b]));
+        }
+    }
+
+    #[test]
+    fn test_modulo() {
+        // was wrap() in 2022 day 20
+        assert_eq!(modulo(0, 6), 0);
+        assert_eq!(modulo(6, 6), 0);
+        assert_eq!(modulo(7, 6), 1);
+        assert_eq!(modulo(66, 6), 0);
+        assert_eq!(modulo(-1, 6), 5);
+        assert_eq!(modulo(-11, 6), 1);
+        assert_eq!((-7_i64).rem_euclid(4_i64), 1);
+        assert_eq!((-11_i64).rem_euclid(6_i64), 1);
+    }
+
+    #[test]
+    fn test_mod_inv_pow() {
+        assert_eq!(mod_inv(3, 11), Some(4));
+        assert_eq!(mod_inv(-3, 11), Some(7));
+        assert_eq!(mod_inv(4, 8), None);
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(3, 0, 1), 0);
+        assert_eq!(mod_pow(-2, 3, 7), 6);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // non-coprime moduli
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_mod_type() {
+        type M7 = Mod<7>;
+        let a = M7::new(-1);
+        assert_eq!(a.value(), 6);
+        assert_eq!((a + M7::new(3)).value(), 2);
+        assert_eq!((M7::new(2) - M7::new(5)).value(), 4);
+        assert_eq!((a * a).value(), 1);
+        assert_eq!((-M7::new(3)).value(), 4);
+        assert_eq!(M7::new(3).inv(), Some(M7::new(5)));
+        assert_eq!(M7::new(3).pow(6), M7::new(1));
+        let mut b: M7 = 10.into();
+        b *= M7::new(2);
+        b += M7::new(1);
+        b -= M7::new(3);
+        assert_eq!(b.to_string(), "4");
+    }
+}
@@ -0,0 +1,253 @@
+// Memoization helper for recursive searches: a HashMap with a configurable hashing strategy, an
+// optional size bound and hit/miss/size statistics that can be logged in a standard format.
+
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::fmt::{Display, Formatter};
+use std::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+use std::ops::AddAssign;
+
+use log::info;
+
+/// A fast, non-cryptographic hasher (same algorithm as FxHash used by rustc): good for caches
+/// keyed on small integers/tuples, where SipHash (the default) is a noticeable part of the cost.
+#[derive(Default, Clone, Copy)]
+pub struct FastHasher {
+    hash: u64,
+}
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+impl FastHasher {
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FastHasher {
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        for b in chunks.remainder() {
+            self.add(*b as u64);
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add(i as u64);
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add(i as u64);
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add(i as u64);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+}
+
+pub type FastHash = BuildHasherDefault<FastHasher>;
+
+/// Statistics collected by a [Memo].
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub size: usize,
+    pub evictions: u64,
+}
+
+impl CacheStats {
+    /// total number of lookups
+    pub fn calls(&self) -> u64 {
+        self.hits + self.misses
+    }
+
+    /// hits over lookups, as percentage
+    pub fn hit_ratio(&self) -> f64 {
+        if self.calls() == 0 {
+            return 0.0;
+        }
+        100.0 * self.hits as f64 / self.calls() as f64
+    }
+
+    /// logs the stats for the given cache name, so all searches are reported the same way.
+    pub fn report(&self, name: &str) {
+        info!("{name} cache | {self}");
+    }
+}
+
+/// sums stats of several tables, e.g. when a new table is used for each input line
+impl AddAssign for CacheStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.hits += rhs.hits;
+        self.misses += rhs.misses;
+        self.size += rhs.size;
+        self.evictions += rhs.evictions;
+    }
+}
+
+impl Display for CacheStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} calls, {} hits ({:.1}%), {} misses, {} entries, {} evicted",
+               self.calls(), self.hits, self.hit_ratio(), self.misses, self.size, self.evictions)
+    }
+}
+
+/// A memo table for recursive functions. Lookups go through [Memo::get] so every call is counted
+/// as hit or miss; results are stored with [Memo::insert].
+///
+/// If a size bound is given, the table is flushed whenever it's full: simpler and cheaper than
+/// LRU, and searches usually have strong locality so recent entries get quickly repopulated.
+pub struct Memo<K, V, S = RandomState> {
+    cache: HashMap<K, V, S>,
+    bound: Option<usize>,
+    stats: CacheStats,
+}
+
+impl<K: Hash + Eq, V> Memo<K, V, RandomState> {
+    /// creates an unbounded memo table using the default (SipHash) hashing.
+    pub fn new() -> Memo<K, V, RandomState> {
+        Memo::with_hasher(RandomState::new())
+    }
+}
+
+impl<K: Hash + Eq, V> Default for Memo<K, V, RandomState> {
+    fn default() -> Self {
+        Memo::new()
+    }
+}
+
+impl<K: Hash + Eq, V> Memo<K, V, FastHash> {
+    /// creates an unbounded memo table using [FastHasher].
+    pub fn fast() -> Memo<K, V, FastHash> {
+        Memo::with_hasher(FastHash::default())
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> Memo<K, V, S> {
+    /// creates an unbounded memo table with the given hashing strategy.
+    pub fn with_hasher(hasher: S) -> Memo<K, V, S> {
+        Memo {
+            cache: HashMap::with_hasher(hasher),
+            bound: None,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// limits the table to at most `bound` entries.
+    pub fn bounded(mut self, bound: usize) -> Memo<K, V, S> {
+        assert!(bound > 0, "Memo bound must be positive");
+        self.bound = Some(bound);
+        self
+    }
+
+    /// looks up the key, recording a hit or a miss.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let value = self.cache.get(key);
+        if value.is_some() {
+            self.stats.hits += 1;
+        } else {
+            self.stats.misses += 1;
+        }
+        value
+    }
+
+    /// checks if the key is in the table, without recording anything.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.cache.contains_key(key)
+    }
+
+    /// stores a value, flushing the table first if it's bounded and full.
+    pub fn insert(&mut self, key: K, value: V) {
+        if let Some(bound) = self.bound {
+            if self.cache.len() >= bound && !self.cache.contains_key(&key) {
+                self.stats.evictions += self.cache.len() as u64;
+                self.cache.clear();
+            }
+        }
+        self.cache.insert(key, value);
+    }
+
+    /// all values currently stored
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.cache.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// empties the table and resets the statistics
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.stats = CacheStats::default();
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats { size: self.cache.len(), ..self.stats }
+    }
+
+    /// shortcut to log the stats of this table
+    pub fn report(&self, name: &str) {
+        self.stats().report(name);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::memo::{CacheStats, Memo};
+
+    fn fib(memo: &mut Memo<u64, u64, crate::memo::FastHash>, n: u64) -> u64 {
+        if n < 2 {
+            return n;
+        }
+        if let Some(val) = memo.get(&n) {
+            return *val;
+        }
+        let val = fib(memo, n - 1) + fib(memo, n - 2);
+        memo.insert(n, val);
+        val
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut memo = Memo::fast();
+        assert_eq!(fib(&mut memo, 50), 12586269025);
+        // 49 misses going down (50..2), each one then finds n - 2 cached except the last
+        assert_eq!(memo.stats(), CacheStats { hits: 47, misses: 49, size: 49, evictions: 0 });
+    }
+
+    #[test]
+    fn test_bound() {
+        let mut memo = Memo::new().bounded(3);
+        for i in 0..4 {
+            memo.insert(i, i * 2);
+        }
+        assert_eq!(memo.len(), 1);
+        assert_eq!(memo.get(&3), Some(&6));
+        assert_eq!(memo.get(&0), None);
+        assert_eq!(memo.stats(), CacheStats { hits: 1, misses: 1, size: 1, evictions: 3 });
+        // overwriting an existing key doesn't flush
+        memo.insert(3, 7);
+        assert_eq!(memo.stats().evictions, 3);
+    }
+}
@@ -0,0 +1,202 @@
+// Indexable list for "move this element by k positions" puzzles: elements keep a stable handle
+// (their insertion order) and can be found, removed and inserted by position in O(√n), using
+// a sqrt decomposition (a list of buckets, rebuilt evenly every √n insertions).
+
+use crate::math::modulo;
+
+/// A list of values addressed both by position and by handle, the index at which they were pushed.
+pub struct OrderList<T> {
+    values: Vec<T>,
+    /// handles of the elements, in list order, split in buckets
+    buckets: Vec<Vec<usize>>,
+    /// bucket containing each handle
+    bucket_of: Vec<usize>,
+    /// elements currently in the list (removed ones are not counted)
+    size: usize,
+    bucket_size: usize,
+    /// insertions since the last rebuild
+    inserts: usize,
+}
+
+impl<T> Default for OrderList<T> {
+    fn default() -> Self {
+        OrderList::new()
+    }
+}
+
+impl<T> FromIterator<T> for OrderList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = OrderList::new();
+        iter.into_iter().for_each(|value| { list.push(value); });
+        list.rebuild();
+        list
+    }
+}
+
+impl<T> OrderList<T> {
+    pub fn new() -> OrderList<T> {
+        OrderList {
+            values: Vec::new(),
+            buckets: vec![Vec::new()],
+            bucket_of: Vec::new(),
+            size: 0,
+            bucket_size: 1,
+            inserts: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// appends a new value at the end of the list, returns its handle
+    pub fn push(&mut self, value: T) -> usize {
+        let handle = self.values.len();
+        self.values.push(value);
+        self.bucket_of.push(0);
+        self.insert(self.size, handle);
+        handle
+    }
+
+    pub fn value(&self, handle: usize) -> &T {
+        &self.values[handle]
+    }
+
+    pub fn value_mut(&mut self, handle: usize) -> &mut T {
+        &mut self.values[handle]
+    }
+
+    /// current position of the element with the given handle
+    pub fn position(&self, handle: usize) -> usize {
+        let bucket = self.bucket_of[handle];
+        let before: usize = self.buckets[..bucket].iter().map(|b| b.len()).sum();
+        before + self.buckets[bucket].iter().position(|h| *h == handle).unwrap()
+    }
+
+    /// handle of the element at the given position
+    pub fn handle_at(&self, index: usize) -> usize {
+        let (bucket, offset) = self.locate(index);
+        self.buckets[bucket][offset]
+    }
+
+    pub fn get(&self, index: usize) -> &T {
+        &self.values[self.handle_at(index)]
+    }
+
+    /// takes the element at the given position out of the list, returning its handle; the handle
+    /// stays valid and can be inserted back.
+    pub fn remove(&mut self, index: usize) -> usize {
+        let (bucket, offset) = self.locate(index);
+        self.size -= 1;
+        self.buckets[bucket].remove(offset)
+    }
+
+    /// puts back a removed element (by handle) at the given position
+    pub fn insert(&mut self, index: usize, handle: usize) {
+        let (bucket, offset) = self.locate_insert(index);
+        self.buckets[bucket].insert(offset, handle);
+        self.bucket_of[handle] = bucket;
+        self.size += 1;
+        self.inserts += 1;
+        if self.inserts >= self.bucket_size {
+            self.rebuild();
+        }
+    }
+
+    /// moves an element by `steps` positions (negative is backwards) in a circular list: moving
+    /// over the full list length minus one leaves the element where it was. An element that would
+    /// land at the start of the list goes to the end instead (the same place, circularly).
+    pub fn move_by(&mut self, handle: usize, steps: i64) {
+        if self.len() < 2 {
+            return;
+        }
+        let from = self.position(handle);
+        self.remove(from);
+        let others = self.len() as i64;
+        let to = modulo(from as i64 + steps, others) as usize;
+        let to = if to == 0 { others as usize } else { to };
+        self.insert(to, handle);
+    }
+
+    /// values in list order
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.buckets.iter().flatten().map(|h| &self.values[*h])
+    }
+
+    /// bucket and offset of the element at index
+    fn locate(&self, index: usize) -> (usize, usize) {
+        let mut offset = index;
+        for (bucket, handles) in self.buckets.iter().enumerate() {
+            if offset < handles.len() {
+                return (bucket, offset);
+            }
+            offset -= handles.len();
+        }
+        panic!("Index {index} out of bounds");
+    }
+
+    /// like locate, but also accepts the position right after the last element
+    fn locate_insert(&self, index: usize) -> (usize, usize) {
+        let mut offset = index;
+        for (bucket, handles) in self.buckets.iter().enumerate() {
+            if offset <= handles.len() {
+                return (bucket, offset);
+            }
+            offset -= handles.len();
+        }
+        panic!("Index {index} out of bounds");
+    }
+
+    /// splits again the elements in buckets of ~√n elements
+    fn rebuild(&mut self) {
+        let handles: Vec<usize> = self.buckets.iter().flatten().copied().collect();
+        self.bucket_size = ((handles.len() as f64).sqrt() as usize).max(1);
+        self.buckets = handles.chunks(self.bucket_size).map(|chunk| chunk.to_vec()).collect();
+        if self.buckets.is_empty() {
+            self.buckets.push(Vec::new());
+        }
+        for (bucket, handles) in self.buckets.iter().enumerate() {
+            handles.iter().for_each(|h| self.bucket_of[*h] = bucket);
+        }
+        self.inserts = 0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::orderlist::OrderList;
+
+    #[test]
+    fn test_insert_remove() {
+        let mut list: OrderList<char> = "abcdefghij".chars().collect();
+        assert_eq!(list.len(), 10);
+        assert_eq!(*list.get(3), 'd');
+        assert_eq!(list.position(7), 7);
+        let d = list.remove(3);
+        assert_eq!(*list.value(d), 'd');
+        assert_eq!(list.position(7), 6);
+        list.insert(0, d);
+        let j = list.remove(9);
+        list.insert(5, j);
+        assert_eq!(list.iter().collect::<String>(), "dabcejfghi");
+        for h in 0..10 {
+            assert_eq!(list.handle_at(list.position(h)), h);
+        }
+        let k = list.push('k');
+        assert_eq!(list.position(k), 10);
+    }
+
+    #[test]
+    fn test_move_by() {
+        // 2022 day 20 example
+        let mut list: OrderList<i64> = [1, 2, -3, 3, -2, 0, 4].into_iter().collect();
+        for handle in 0..list.len() {
+            list.move_by(handle, *list.value(handle));
+        }
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, -3, 4, 0, 3, -2]);
+    }
+}
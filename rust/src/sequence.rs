@@ -0,0 +1,129 @@
+// Sequence and polynomial helpers: finite-difference extrapolation, exact Lagrange interpolation
+// and an integer-exact solver for the "hold the button" quadratic inequality.
+
+use std::ops::RangeInclusive;
+
+use num::integer::Roots;
+use num::rational::Ratio;
+
+/// differences between consecutive elements
+pub fn differences(data: &[i64]) -> Vec<i64> {
+    data.windows(2).map(|w| w[1] - w[0]).collect()
+}
+
+/// next value of a polynomial sequence, by summing the last element of each level of differences.
+pub fn extrapolate_forward(data: &[i64]) -> i64 {
+    let mut next = 0;
+    let mut level = data.to_vec();
+    while !level.iter().all(|val| *val == 0) {
+        next += level.last().unwrap();
+        level = differences(&level);
+    }
+    next
+}
+
+/// previous value of a polynomial sequence, same as [extrapolate_forward] on the reversed data.
+pub fn extrapolate_backward(data: &[i64]) -> i64 {
+    let reversed: Vec<i64> = data.iter().rev().copied().collect();
+    extrapolate_forward(&reversed)
+}
+
+/// evaluates at x the unique polynomial going through all the given (x, y) points.
+/// The result is exact: x's must be distinct.
+pub fn lagrange(points: &[(i64, i64)], x: i64) -> Ratio<i128> {
+    let mut total = Ratio::from_integer(0);
+    for (i, &(xi, yi)) in points.iter().enumerate() {
+        let mut term = Ratio::from_integer(yi as i128);
+        for (j, &(xj, _)) in points.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            assert_ne!(xi, xj, "Lagrange interpolation needs distinct x's");
+            term *= Ratio::new((x - xj) as i128, (xi - xj) as i128);
+        }
+        total += term;
+    }
+    total
+}
+
+/// like [lagrange] but returns the value only if it's an integer
+pub fn lagrange_int(points: &[(i64, i64)], x: i64) -> Option<i64> {
+    let val = lagrange(points, x);
+    if val.is_integer() {
+        Some(*val.numer() as i64)
+    } else {
+        None
+    }
+}
+
+/// all integer t in 0..=total for which t * (total - t) > record, or None if there is none.
+/// Computed with integer square root and then adjusted, so there are no rounding issues at the
+/// edges like the float quadratic formula.
+pub fn quadratic_wins(total: i64, record: i64) -> Option<RangeInclusive<i64>> {
+    let (t, d) = (total as i128, record as i128);
+    let delta = t * t - 4 * d;
+    if delta < 0 {
+        return None;
+    }
+    let beats = |x: i128| x * (t - x) > d;
+    // smallest root is (t - sqrt(delta)) / 2 - start from there and find the first integer beating
+    let mut low = ((t - delta.sqrt()) / 2).max(0);
+    while low > 0 && beats(low - 1) {
+        low -= 1;
+    }
+    // the vertex is at t/2: if that doesn't beat the record, nothing does
+    while low <= t / 2 && !beats(low) {
+        low += 1;
+    }
+    if low > t / 2 {
+        return None;
+    }
+    // the parabola is symmetric around t/2
+    let high = t - low;
+    Some(low as i64..=high as i64)
+}
+
+#[cfg(test)]
+mod test {
+    use num::rational::Ratio;
+    use crate::sequence::{extrapolate_backward, extrapolate_forward, lagrange, lagrange_int, quadratic_wins};
+
+    #[test]
+    fn test_extrapolate() {
+        let data = [(vec![0, 3, 6, 9, 12, 15], 18, -3),
+            (vec![1, 3, 6, 10, 15, 21], 28, 0),
+            (vec![10, 13, 16, 21, 30, 45], 68, 5)];
+        for (seq, next, prev) in data {
+            assert_eq!(extrapolate_forward(&seq), next);
+            assert_eq!(extrapolate_backward(&seq), prev);
+        }
+    }
+
+    #[test]
+    fn test_lagrange() {
+        // x^2 + 1
+        let points = [(0, 1), (1, 2), (2, 5)];
+        assert_eq!(lagrange_int(&points, 10), Some(101));
+        assert_eq!(lagrange_int(&points, -3), Some(10));
+        // line through (0, 0) and (2, 1) at x = 1 is 1/2
+        assert_eq!(lagrange(&[(0, 0), (2, 1)], 1), Ratio::new(1, 2));
+        assert_eq!(lagrange_int(&[(0, 0), (2, 1)], 1), None);
+    }
+
+    #[test]
+    fn test_quadratic_wins() {
+        assert_eq!(quadratic_wins(7, 9), Some(2..=5));
+        assert_eq!(quadratic_wins(15, 40), Some(4..=11));
+        // roots are exactly 10 and 20: both must be excluded
+        assert_eq!(quadratic_wins(30, 200), Some(11..=19));
+        assert_eq!(quadratic_wins(71530, 940200), Some(14..=71516));
+        // t * (T - t) peaks at 4 for T = 4
+        assert_eq!(quadratic_wins(4, 4), None);
+        assert_eq!(quadratic_wins(4, 10), None);
+        assert_eq!(quadratic_wins(4, 3), Some(2..=2));
+        // a record exactly at the peak, for a large T, must not walk all the way to T
+        let t = 3_000_000_000;
+        assert_eq!(quadratic_wins(t, t * t / 4), None);
+        assert_eq!(quadratic_wins(t, t * t / 4 - 1), Some(t / 2..=t / 2));
+    }
+}
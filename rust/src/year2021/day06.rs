@@ -2,9 +2,13 @@
 // of course for part 2 there's no need to keep track of _each_ lanternfish, just the count for each
 // day, which makes all a simple count (provided we using u64 as number increase)
 
+// Later: the day by day rotation is a linear recurrence, so a transition matrix raised to the number
+// of days jumps there directly.
+
 use std::str::FromStr;
 use std::usize;
 use log::info;
+use adventofcode::linalg::Transitions;
 use adventofcode::Solver;
 
 pub struct Solution {
@@ -19,8 +23,10 @@ impl Solution {
     }
 }
 
-const DAYS1: u32 = 80;
-const DAYS2: u32 = 256;
+const DAYS1: u64 = 80;
+const DAYS2: u64 = 256;
+const NEW_TIMER: usize = 8;
+const RESET_TIMER: usize = 6;
 
 impl Solver for Solution {
     fn parse(&mut self, line: &str) {
@@ -28,24 +34,17 @@ impl Solver for Solution {
     }
 
     fn solve(&mut self) -> Option<(String, String)> {
-        let mut part1 = 0;
-        for day in 0..DAYS2 {
-            if day == DAYS1 {
-                part1 = self.timers.iter().sum();
-            }
-            // get new born
-            let born = self.timers[0];
-            // shift down all
-            for i in 0..self.timers.len() - 1 {
-                self.timers[i] = self.timers[i + 1];
-            }
-            // 9 and 7 because we calculate the day before
-            self.timers[8] = born;
-            self.timers[6] += born;
-        }
+        let mut fish = Transitions::new();
+        (0..=NEW_TIMER).for_each(|timer| { fish.state(&timer); });
+        (1..=NEW_TIMER).for_each(|timer| { fish.add(&timer, &(timer - 1), 1); });
+        fish.add(&0, &RESET_TIMER, 1).add(&0, &NEW_TIMER, 1);
+        let matrix = fish.matrix();
+        // states were added in timer order, so the counts are already the start vector
+        let start = self.timers.to_vec();
 
+        let part1: u64 = matrix.pow(DAYS1).apply(&start).iter().sum();
         info!("[1] After {} days: {} lanternfish", DAYS1, part1);
-        let part2: u64 = self.timers.iter().sum();
+        let part2: u64 = matrix.pow(DAYS2).apply(&start).iter().sum();
         info!("[2] After {} days: {} lanternfish", DAYS2, part2);
         Some((part1.to_string(), part2.to_string()))
     }
@@ -1,8 +1,12 @@
 // https://adventofcode.com/2021/day/14
-// TODO
+// The polymer doubles at every step, but what matters is only how many of each pair there are:
+// every pair AB with a rule AB -> C becomes one AC and one CB, a linear transition on the pair
+// counts that a matrix power jumps over in one go. Each element is counted as the first of its
+// pairs, plus the very last element of the template (which never changes).
 
 use std::collections::HashMap;
 use log::{debug, info};
+use adventofcode::linalg::Transitions;
 use adventofcode::Solver;
 
 pub struct Solution {
@@ -17,8 +21,23 @@ impl Solution {
             mapping: HashMap::new(),
         }
     }
+
+    /// difference between the most and least common elements after the given steps
+    fn spread(&self, pairs: &Transitions<(char, char)>, start: &[u64], steps: u64) -> u64 {
+        let counts = pairs.matrix().pow(steps).apply(start);
+        let mut elements: HashMap<char, u64> = HashMap::from([(*self.polymer.last().unwrap(), 1)]);
+        // some pairs (made by a rule) can be missing at this step: only count the elements present
+        for (pair, count) in pairs.states().iter().zip(counts).filter(|(_, c)| *c > 0) {
+            *elements.entry(pair.0).or_insert(0) += count;
+        }
+        debug!("Counts after {steps} steps: {:?}", elements);
+        elements.values().max().unwrap() - elements.values().min().unwrap()
+    }
 }
 
+const STEPS1: u64 = 10;
+const STEPS2: u64 = 40;
+
 impl Solver for Solution {
     fn parse(&mut self, line: &str) {
         if line.is_empty() {
@@ -37,101 +56,25 @@ impl Solver for Solution {
     fn solve(&mut self) -> Option<(String, String)> {
         info!("Folding polymer {:?}", self.polymer);
 
-        let mut cache = HashMap::new();
-        let mut counts = HashMap::new();
-        for i in 0..self.polymer.len() - 1 {
-            let key = (self.polymer[i], self.polymer[i + 1]);
-            let folding = cache.entry(key)
-                .or_insert_with(|| Folding::create(key, &mut self.mapping, 10));
-            folding.merge_counts(&mut counts);
-            counts.entry(key.0).and_modify(|val| *val -= 1);
-        }
-
-        debug!("Counts after 10 folds: {:?}", counts);
-        let mut counts: Vec<(char, usize)> = counts.into_iter().collect();
-        counts.sort_by_key(|val| val.1);
-
-        let first = counts.first().unwrap();
-        let last = counts.last().unwrap();
-        let part1 = last.1 - first.1;
-        info!("[1] Polymer: first {:?}, last {:?} => {}", first, last, part1);
-
-        // now for the 40 part, fold all to 20, then, per every resulting final polymer segment,
-        // merge only the counts
-        let mut cache = HashMap::new();
-        let mut counts = HashMap::new();
-        for i in 0..self.polymer.len() - 1 {
-            let key = (self.polymer[i], self.polymer[i + 1]);
-            let folding = cache.entry(key)
-                .or_insert_with(|| Folding::create(key, &mut self.mapping, 20));
-
-            // explore previous polymer (fold and cache)
-            let polymer = folding.polymer.clone();
-            for i in 0..polymer.len() - 1 {
-                let key = (polymer[i], polymer[i + 1]);
-                let folding = cache.entry(key)
-                    .or_insert_with(|| Folding::create(key, &mut self.mapping, 20));
-                folding.merge_counts(&mut counts);
-                counts.entry(key.0).and_modify(|val| *val -= 1);
-            }
-            debug!("part 2 [{}/{}] {:?} => folded to 40", i, self.polymer.len(), key);
+        let mut pairs = Transitions::new();
+        for (pair, inserted) in &self.mapping {
+            pairs.add(pair, &(pair.0, *inserted), 1).add(pair, &(*inserted, pair.1), 1);
         }
-        debug!("Counts after 40 folds: {:?}", counts);
-        let mut counts: Vec<(char, usize)> = counts.into_iter().collect();
-        counts.sort_by_key(|val| val.1);
-
-        let first = counts.first().unwrap();
-        let last = counts.last().unwrap();
-        let part2 = last.1 - first.1;
-        info!("[2] Polymer: first {:?}, last {:?} => {}", first, last, part2);
+        let template: Vec<(char, char)> = self.polymer.windows(2).map(|w| (w[0], w[1])).collect();
+        template.iter().for_each(|pair| { pairs.state(pair); });
+        // pairs without a rule (in the template or made by a rule) never change
+        let unchanged: Vec<(char, char)> = pairs.states().iter()
+            .filter(|pair| !self.mapping.contains_key(pair))
+            .copied()
+            .collect();
+        unchanged.iter().for_each(|pair| { pairs.add(pair, pair, 1); });
+        let start = pairs.vector(template.iter().map(|pair| (pair, 1)));
+
+        let part1 = self.spread(&pairs, &start, STEPS1);
+        info!("[1] Polymer after {STEPS1} steps => {part1}");
+        let part2 = self.spread(&pairs, &start, STEPS2);
+        info!("[2] Polymer after {STEPS2} steps => {part2}");
 
         Some((part1.to_string(), part2.to_string()))
     }
 }
-
-struct Folding {
-    counts: HashMap<char, usize>,
-    polymer: Vec<char>,
-}
-
-impl Folding {
-    fn create(key: (char, char), mappings: &HashMap<(char, char), char>, folds: usize) -> Folding {
-        let mut counts = HashMap::new();
-        [key.0, key.1].iter().for_each(|v| {
-            counts.entry(v.clone()).and_modify(|val| *val += 1).or_insert(1);
-        });
-        let polymer = vec![key.0, key.1];
-        let mut folding = Folding {
-            counts,
-            polymer,
-        };
-        folding.fold(mappings, folds);
-        debug!("{:?} Created and folded {} times", key, folds);
-        folding
-    }
-
-    fn fold(&mut self, mappings: &HashMap<(char, char), char>, folds: usize) {
-        for _ in 0..folds {
-            let sequence = self.polymer.clone();
-            let mut next = Vec::with_capacity(2 * sequence.len());
-            for i in 0..sequence.len() - 1 {
-                let pair = (sequence[i], sequence[i+1]);
-                let generated = mappings.get(&pair).unwrap();
-                next.push(pair.0);
-                next.push(*generated);
-            }
-            next.push(sequence[sequence.len() - 1]);
-            self.polymer = next;
-        }
-        self.counts.clear();
-        self.polymer.iter().for_each(|ch| {
-            self.counts.entry(*ch).and_modify(|val| *val += 1).or_insert(1);
-        });
-    }
-
-    fn merge_counts(&self, counts: &mut HashMap<char, usize>) {
-        self.counts.iter().for_each(|(ch, count)|
-            { counts.entry(*ch).and_modify(|val| *val += count).or_insert(*count); }
-        );
-    }
-}
\ No newline at end of file
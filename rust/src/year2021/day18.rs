@@ -1,5 +1,9 @@
 // https://adventofcode.com/2021/day/18
 
+use std::fmt::{Display, Formatter};
+use std::ops::Add;
+use std::str::FromStr;
+use log::{debug, info};
 use adventofcode::Solver;
 
 pub struct Solution {
@@ -16,23 +20,304 @@ impl Solution {
 
 impl Solver for Solution {
     fn parse(&mut self, line: &str) {
-        self.numbers.push(SnailNumber::parse(line));
+        if line.is_empty() {
+            return;
+        }
+        match SnailNumber::from_str(line) {
+            Ok(number) => self.numbers.push(number),
+            Err(err) => panic!("Invalid snailfish number {line}: {err}"),
+        }
     }
 
     fn solve(&mut self) -> Option<(String, String)> {
-        todo!()
+        let total = self.numbers.iter().cloned()
+            .reduce(|acc, n| acc + n)
+            .unwrap();
+        debug!("Final sum: {total}");
+        let part1 = total.magnitude();
+        info!("[1] Magnitude of the final sum: {part1}");
+
+        let part2 = largest_pair(&self.numbers);
+        info!("[2] Largest magnitude of any two numbers: {part2}");
+
+        Some((part1.to_string(), part2.to_string()))
     }
 }
 
+/// largest magnitude of the sum of two different numbers
+fn largest_pair(numbers: &[SnailNumber]) -> u32 {
+    // addition is not commutative: try both orders of every pair
+    let mut largest = 0;
+    for (i, a) in numbers.iter().enumerate() {
+        for (j, b) in numbers.iter().enumerate() {
+            if i != j {
+                largest = largest.max((a.clone() + b.clone()).magnitude());
+            }
+        }
+    }
+    largest
+}
+
+#[derive(Clone, Debug, PartialEq)]
 enum SnailValue {
-    List(Vec<SnailNumber>),
+    Pair(Box<SnailNumber>),
     Value(u32),
 }
 
+#[derive(Clone, Debug, PartialEq)]
 struct SnailNumber(SnailValue, SnailValue);
 
+const EXPLODE_DEPTH: u32 = 4;
+const SPLIT_VALUE: u32 = 10;
+
+impl SnailValue {
+    /// explodes the leftmost pair nested deep enough, returning the values that still need to be
+    /// added to the nearest regular numbers on the left and on the right (0 once they're added).
+    fn explode(&mut self, depth: u32) -> Option<(u32, u32)> {
+        let SnailValue::Pair(pair) = self else {
+            return None;
+        };
+        if depth >= EXPLODE_DEPTH {
+            if let (SnailValue::Value(left), SnailValue::Value(right)) = (&pair.0, &pair.1) {
+                let carry = (*left, *right);
+                *self = SnailValue::Value(0);
+                return Some(carry);
+            }
+        }
+        pair.explode(depth + 1)
+    }
+
+    fn add_leftmost(&mut self, val: u32) {
+        match self {
+            SnailValue::Value(v) => *v += val,
+            SnailValue::Pair(pair) => pair.0.add_leftmost(val),
+        }
+    }
+
+    fn add_rightmost(&mut self, val: u32) {
+        match self {
+            SnailValue::Value(v) => *v += val,
+            SnailValue::Pair(pair) => pair.1.add_rightmost(val),
+        }
+    }
+
+    /// splits the leftmost regular number >= 10, returns true if it did
+    fn split(&mut self) -> bool {
+        match self {
+            SnailValue::Value(v) if *v >= SPLIT_VALUE => {
+                let pair = SnailNumber(SnailValue::Value(*v / 2), SnailValue::Value(*v - *v / 2));
+                *self = SnailValue::Pair(Box::new(pair));
+                true
+            }
+            SnailValue::Value(_) => false,
+            SnailValue::Pair(pair) => pair.split(),
+        }
+    }
+
+    fn magnitude(&self) -> u32 {
+        match self {
+            SnailValue::Value(v) => *v,
+            SnailValue::Pair(pair) => pair.magnitude(),
+        }
+    }
+}
+
 impl SnailNumber {
-    fn parse(text: &str) -> SnailNumber {
-        SnailNumber(SnailValue::Value(0), SnailValue::Value(0))
+    fn explode(&mut self, depth: u32) -> Option<(u32, u32)> {
+        if let Some((left, right)) = self.0.explode(depth) {
+            self.1.add_leftmost(right);
+            return Some((left, 0));
+        }
+        if let Some((left, right)) = self.1.explode(depth) {
+            self.0.add_rightmost(left);
+            return Some((0, right));
+        }
+        None
+    }
+
+    fn split(&mut self) -> bool {
+        self.0.split() || self.1.split()
+    }
+
+    fn reduce(&mut self) {
+        // explode always takes precedence over split
+        while self.explode(1).is_some() || self.split() {}
+    }
+
+    fn magnitude(&self) -> u32 {
+        3 * self.0.magnitude() + 2 * self.1.magnitude()
+    }
+}
+
+impl Add for SnailNumber {
+    type Output = SnailNumber;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut result = SnailNumber(SnailValue::Pair(Box::new(self)), SnailValue::Pair(Box::new(rhs)));
+        result.reduce();
+        result
+    }
+}
+
+impl FromStr for SnailNumber {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { text: s.as_bytes(), pos: 0 };
+        let number = parser.pair()?;
+        if parser.pos != s.len() {
+            return Err(format!("unexpected '{}' at {}", s[parser.pos..].chars().next().unwrap(), parser.pos));
+        }
+        Ok(number)
+    }
+}
+
+struct Parser<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn expect(&mut self, ch: u8) -> Result<(), String> {
+        match self.text.get(self.pos) {
+            Some(&c) if c == ch => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(&c) => Err(format!("expected '{}' at {}, found '{}'", ch as char, self.pos, c as char)),
+            None => Err(format!("expected '{}' at {}, found end of line", ch as char, self.pos)),
+        }
+    }
+
+    fn pair(&mut self) -> Result<SnailNumber, String> {
+        self.expect(b'[')?;
+        let left = self.value()?;
+        self.expect(b',')?;
+        let right = self.value()?;
+        self.expect(b']')?;
+        Ok(SnailNumber(left, right))
+    }
+
+    fn value(&mut self) -> Result<SnailValue, String> {
+        if self.text.get(self.pos) == Some(&b'[') {
+            return Ok(SnailValue::Pair(Box::new(self.pair()?)));
+        }
+        let start = self.pos;
+        while self.pos < self.text.len() && self.text[self.pos].is_ascii_digit() {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(format!("expected number or pair at {}", start));
+        }
+        let digits = std::str::from_utf8(&self.text[start..self.pos]).unwrap();
+        u32::from_str(digits).map(SnailValue::Value).map_err(|err| format!("invalid number {digits}: {err}"))
+    }
+}
+
+impl Display for SnailValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnailValue::Value(v) => write!(f, "{v}"),
+            SnailValue::Pair(pair) => write!(f, "{pair}"),
+        }
+    }
+}
+
+impl Display for SnailNumber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{},{}]", self.0, self.1)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use super::*;
+
+    fn snail(text: &str) -> SnailNumber {
+        SnailNumber::from_str(text).unwrap()
+    }
+
+    fn sum(lines: &[&str]) -> SnailNumber {
+        lines.iter().map(|l| snail(l)).reduce(|acc, n| acc + n).unwrap()
+    }
+
+    #[test]
+    fn test_parse_display() {
+        for text in ["[1,2]", "[[1,2],3]", "[9,[8,7]]", "[[[[1,3],[5,3]],[[1,3],[8,7]]],[[[4,9],[6,9]],[[8,2],[7,3]]]]"] {
+            assert_eq!(snail(text).to_string(), text);
+        }
+        assert!(SnailNumber::from_str("[1,2").is_err());
+        assert!(SnailNumber::from_str("[1;2]").is_err());
+        assert!(SnailNumber::from_str("[1,2]]").is_err());
+        assert!(SnailNumber::from_str("[,2]").is_err());
+    }
+
+    #[test]
+    fn test_explode() {
+        let data = [("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]"),
+            ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]"),
+            ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]"),
+            ("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]", "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]"),
+            ("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]", "[[3,[2,[8,0]]],[9,[5,[7,0]]]]")];
+        for (before, after) in data {
+            let mut number = snail(before);
+            assert!(number.explode(1).is_some());
+            assert_eq!(number.to_string(), after);
+        }
+    }
+
+    #[test]
+    fn test_add() {
+        let result = snail("[[[[4,3],4],4],[7,[[8,4],9]]]") + snail("[1,1]");
+        assert_eq!(result.to_string(), "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]");
+        assert_eq!(sum(&["[1,1]", "[2,2]", "[3,3]", "[4,4]", "[5,5]", "[6,6]"]).to_string(),
+                   "[[[[5,0],[7,4]],[5,5]],[6,6]]");
+        let larger = sum(&["[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]",
+            "[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]",
+            "[[2,[[0,8],[3,4]]],[[[6,7],1],[7,[1,6]]]]",
+            "[[[[2,4],7],[6,[0,5]]],[[[6,8],[2,8]],[[2,1],[4,5]]]]",
+            "[7,[5,[[3,8],[1,4]]]]",
+            "[[2,[2,2]],[8,[8,1]]]",
+            "[2,9]",
+            "[1,[[[9,3],9],[[9,0],[0,7]]]]",
+            "[[[5,[7,4]],7],1]",
+            "[[[[4,2],2],6],[8,7]]"]);
+        assert_eq!(larger.to_string(), "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]");
+    }
+
+    #[test]
+    fn test_magnitude() {
+        let data = [("[[1,2],[[3,4],5]]", 143),
+            ("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", 1384),
+            ("[[[[1,1],[2,2]],[3,3]],[4,4]]", 445),
+            ("[[[[3,0],[5,3]],[4,4]],[5,5]]", 791),
+            ("[[[[5,0],[7,4]],[5,5]],[6,6]]", 1137),
+            ("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]", 3488)];
+        for (text, magnitude) in data {
+            assert_eq!(snail(text).magnitude(), magnitude);
+        }
+    }
+
+    #[test]
+    fn test_homework() {
+        let lines = ["[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]",
+            "[[[5,[2,8]],4],[5,[[9,9],0]]]",
+            "[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]",
+            "[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]",
+            "[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]",
+            "[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]",
+            "[[[[5,4],[7,7]],8],[[8,3],8]]",
+            "[[9,3],[[9,9],[6,[4,9]]]]",
+            "[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]",
+            "[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]"];
+        let total = sum(&lines);
+        assert_eq!(total.to_string(), "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]");
+        assert_eq!(total.magnitude(), 4140);
+        let homework: Vec<SnailNumber> = lines.iter().map(|l| snail(l)).collect();
+        assert_eq!(largest_pair(&homework), 3993);
+        // the best pair only works in one order
+        assert_eq!(largest_pair(&[homework[8].clone(), homework[0].clone()]), 3993);
+        assert!((homework[0].clone() + homework[8].clone()).magnitude() < 3993);
+    }
+}
@@ -8,6 +8,7 @@ use std::usize;
 use log::{debug, info};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use adventofcode::math::lcm_all;
 use adventofcode::Solver;
 
 pub(crate) struct Solution {
@@ -77,7 +78,8 @@ impl Solver for Solution {
     }
 
     fn solve(&mut self) -> Option<(String, String)> {
-        let reducer = self.monkeys.iter().map(|m| m.test.0).product();
+        // worry levels only matter modulo each monkey's divisor, so keep them within the lcm of all
+        let reducer = lcm_all(self.monkeys.iter().map(|m| m.test.0 as i64)) as u64;
         debug!("Reducer: {reducer}");
 
         let part1_inspected = self.run_loops(20, 3, reducer);
@@ -14,6 +14,7 @@ use std::str::FromStr;
 use std::time::SystemTime;
 use once_cell::sync::Lazy;
 use regex::{Captures, Regex};
+use adventofcode::memo::Memo;
 use adventofcode::Solver;
 use factorial::Factorial;
 use log::{debug, info};
@@ -96,18 +97,19 @@ impl Solver for Solution {
         let mut one_path = OnePathSolver::new();
         let best_path1 = one_path.find_path(&self, OnePath::new(START));
         let t1 = SystemTime::now();
-        info!("[1] Found max flow is {}: {:?} ({} cache hits, {} calls, {} cache size) [{:.3}sec]",
-                 best_path1.total_flow, best_path1.visited, one_path.cache_hits, one_path.calls,
-                 one_path.cache.len(), t1.duration_since(t0).unwrap().as_secs_f32());
+        info!("[1] Found max flow is {}: {:?} [{:.3}sec]",
+                 best_path1.total_flow, best_path1.visited, t1.duration_since(t0).unwrap().as_secs_f32());
+        one_path.cache.report("[1] paths");
 
         // part 2
         let t0 = SystemTime::now();
         let mut two_path = TwoPathsSolver::new();
         let best_path2 = two_path.find_path(&self, TwoPaths::new(START));
         let t1 = SystemTime::now();
-        info!("[2] Found max flow is {}: {:?} / {:?} ({} cache hits, {} calls, {} cache size) [{:.3}sec]",
-                 best_path2.total_flow, best_path2.human_path, best_path2.ele_path, two_path.cache_hits,
-                 two_path.calls, two_path.cache.len(), t1.duration_since(t0).unwrap().as_secs_f32());
+        info!("[2] Found max flow is {}: {:?} / {:?} [{:.3}sec]",
+                 best_path2.total_flow, best_path2.human_path, best_path2.ele_path,
+                 t1.duration_since(t0).unwrap().as_secs_f32());
+        two_path.cache.report("[2] paths");
         Some((best_path1.total_flow.to_string(), best_path2.total_flow.to_string()))
     }
 }
@@ -197,32 +199,25 @@ impl OnePath {
 }
 
 struct OnePathSolver {
-    calls: u32,
-    cache: HashMap<OnePathKey, OnePath>,
-    cache_hits: u32,
+    cache: Memo<OnePathKey, OnePath>,
 }
 
 impl OnePathSolver {
     fn new() -> OnePathSolver {
         OnePathSolver {
-            calls: 0,
-            cache: HashMap::new(),
-            cache_hits: 0,
+            cache: Memo::new(),
         }
     }
 
     fn find_path(&mut self, data: &Solution, path: OnePath) -> OnePath {
-        self.calls += 1;
-        if (self.calls % 1000000) == 0 {
-            info!("{} calls, {} cache hits...", self.calls, self.cache_hits)
-        }
         let cave = path.visited.last().unwrap();
         let cache_key = path.cache_key();
-        if self.cache.contains_key(&cache_key) {
-            self.cache_hits += 1;
-            let cached = &self.cache[&cache_key];
+        if let Some(cached) = self.cache.get(&cache_key) {
             return path.merge(cached);
         }
+        if (self.cache.stats().misses % 1000000) == 0 {
+            info!("{}...", self.cache.stats())
+        }
 
         let mut best_path = path.clone();
         for i in 0..data.valves_with_flow.len() {
@@ -352,34 +347,27 @@ impl TwoPaths {
 }
 
 struct TwoPathsSolver {
-    calls: u32,
-    cache: HashMap<TwoPathsKey, TwoPaths>,
-    cache_hits: u32,
+    cache: Memo<TwoPathsKey, TwoPaths>,
 }
 
 impl TwoPathsSolver {
     fn new() -> TwoPathsSolver {
         TwoPathsSolver {
-            calls: 0,
-            cache: HashMap::new(),
-            cache_hits: 0,
+            cache: Memo::new(),
         }
     }
 
     fn find_path(&mut self, data: &Solution, path: TwoPaths) -> TwoPaths {
-        self.calls += 1;
-        if (self.calls % 1000000) == 0 {
-            info!("{} calls, {} cache hits...", self.calls, self.cache_hits)
-        }
         let man_pos = path.human_path.last().unwrap();
         let ele_pos = path.ele_path.last().unwrap();
         let cache_key = path.cache_key();
 
-        if self.cache.contains_key(&cache_key) {
-            self.cache_hits += 1;
-            let cached = &self.cache[&cache_key];
+        if let Some(cached) = self.cache.get(&cache_key) {
             return path.merge(cached);
         }
+        if (self.cache.stats().misses % 1000000) == 0 {
+            info!("{}...", self.cache.stats())
+        }
 
         let mut best_path = path.clone();
         for i in 0..data.valves_with_flow.len() {
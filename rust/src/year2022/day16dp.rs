@@ -0,0 +1,183 @@
+// Third version of day16, redesigned instead of optimised:
+// - only valves with flow matter: they get compressed to indices 0..n, so the set of open valves
+//   is a bitmask and all the state is plain integers
+// - distances between all of them (and the start) are precomputed once in a matrix
+// - a single DFS visits every reachable order of opening valves and records, for each bitmask of
+//   opened valves, the best pressure that can be released opening exactly those
+// - part 2 is then just pairing two disjoint masks (one for the human, one for the elephant)
+// From minutes (day16) to milliseconds.
+
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+use std::time::SystemTime;
+use log::{debug, info};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use adventofcode::Solver;
+
+pub(crate) struct Solution {
+    names: Vec<String>,
+    flows: Vec<u32>,
+    tunnels: Vec<Vec<String>>,
+}
+
+static RE_VALVE: Lazy<Regex> = Lazy::new(|| Regex::new(r"Valve (\S+) has flow rate=(\d+); tunnels? leads? to valves? (.*)").unwrap());
+const PART1_MINUTES: u32 = 30;
+const PART2_MINUTES: u32 = 26;
+const START: &str = "AA";
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            names: Vec::new(),
+            flows: Vec::new(),
+            tunnels: Vec::new(),
+        }
+    }
+
+    /// compresses the valves with flow to 0..n (start is n) and returns their flows and the matrix
+    /// of distances between them
+    fn compress(&self) -> (Vec<u32>, Vec<Vec<u32>>) {
+        let ids: HashMap<&str, usize> = self.names.iter().enumerate()
+            .map(|(i, name)| (name.as_str(), i))
+            .collect();
+        let mut useful: Vec<usize> = (0..self.names.len()).filter(|i| self.flows[*i] > 0).collect();
+        useful.push(ids[START]);
+
+        let distances = useful.iter()
+            .map(|from| {
+                // BFS from each useful valve over the whole map
+                let mut all = vec![u32::MAX; self.names.len()];
+                all[*from] = 0;
+                let mut queue = VecDeque::from([*from]);
+                while let Some(valve) = queue.pop_front() {
+                    for next in &self.tunnels[valve] {
+                        let next = ids[next.as_str()];
+                        if all[next] == u32::MAX {
+                            all[next] = all[valve] + 1;
+                            queue.push_back(next);
+                        }
+                    }
+                }
+                useful.iter().map(|to| all[*to]).collect()
+            })
+            .collect();
+        let flows = useful.iter().map(|i| self.flows[*i]).collect();
+        (flows, distances)
+    }
+}
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        if let Some(captures) = RE_VALVE.captures(line) {
+            self.names.push(String::from(&captures[1]));
+            self.flows.push(u32::from_str(&captures[2]).unwrap());
+            self.tunnels.push(captures[3].split(", ").map(String::from).collect());
+        }
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        let (flows, distances) = self.compress();
+        let valves = Valves { flows, distances };
+        info!("Valves with flow: {}", valves.count());
+
+        let t0 = SystemTime::now();
+        let part1 = *valves.best_by_mask(PART1_MINUTES).iter().max().unwrap();
+        let t1 = SystemTime::now();
+        info!("[1] Found max flow is {part1} [{:.3}sec]", t1.duration_since(t0).unwrap().as_secs_f32());
+
+        let t0 = SystemTime::now();
+        let best = valves.best_by_mask(PART2_MINUTES);
+        let part2 = best_disjoint_pair(&best);
+        let t1 = SystemTime::now();
+        info!("[2] Found max flow is {part2} [{:.3}sec]", t1.duration_since(t0).unwrap().as_secs_f32());
+
+        Some((part1.to_string(), part2.to_string()))
+    }
+}
+
+struct Valves {
+    /// flow of each valve with flow; the start valve is the last, with 0 flow
+    flows: Vec<u32>,
+    distances: Vec<Vec<u32>>,
+}
+
+impl Valves {
+    /// number of valves with flow (start excluded)
+    fn count(&self) -> usize {
+        self.flows.len() - 1
+    }
+
+    /// best pressure released by opening exactly the valves in each mask within the given time
+    fn best_by_mask(&self, minutes: u32) -> Vec<u32> {
+        let mut best = vec![0; 1 << self.count()];
+        self.visit(self.count(), minutes, 0, 0, &mut best);
+        debug!("{} reachable sets of valves in {minutes} minutes", best.iter().filter(|v| **v > 0).count());
+        best
+    }
+
+    fn visit(&self, valve: usize, minutes_left: u32, opened: usize, pressure: u32, best: &mut [u32]) {
+        best[opened] = best[opened].max(pressure);
+        for next in 0..self.count() {
+            if opened & (1 << next) != 0 {
+                continue;
+            }
+            // walk there and open it
+            let cost = self.distances[valve][next] + 1;
+            if cost >= minutes_left {
+                continue;
+            }
+            let left = minutes_left - cost;
+            self.visit(next, left, opened | (1 << next), pressure + left * self.flows[next], best);
+        }
+    }
+}
+
+/// best sum of two disjoint masks: first spread each mask's value to all its supersets (so that
+/// best_subset[m] is the best of any subset of m), then pair each mask with its complement.
+fn best_disjoint_pair(best: &[u32]) -> u32 {
+    let full = best.len() - 1;
+    let mut best_subset = best.to_vec();
+    let mut bit = 1;
+    while bit < best.len() {
+        for mask in 0..best.len() {
+            if mask & bit != 0 {
+                best_subset[mask] = best_subset[mask].max(best_subset[mask ^ bit]);
+            }
+        }
+        bit <<= 1;
+    }
+    (0..best.len())
+        .map(|mask| best[mask] + best_subset[full ^ mask])
+        .max()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_best_disjoint_pair() {
+        // 2 valves: alone they're worth 5 and 7, together only 10 (e.g. too far apart)
+        assert_eq!(best_disjoint_pair(&[0, 5, 7, 10]), 12);
+        assert_eq!(best_disjoint_pair(&[0, 5, 7, 15]), 15);
+    }
+
+    #[test]
+    fn test_best_by_mask() {
+        // valves 0 (flow 10) and 1 (flow 5), start is 2: one step to 0, two steps to 1 or between 0 and 1
+        let valves = Valves {
+            flows: vec![10, 5, 0],
+            distances: vec![vec![0, 2, 1], vec![2, 0, 2], vec![1, 2, 0]],
+        };
+        // in 5 minutes: 0 is open for 3 minutes, 1 for 2 minutes, there's no time to open both
+        assert_eq!(valves.best_by_mask(5), vec![0, 30, 10, 0]);
+        assert_eq!(best_disjoint_pair(&valves.best_by_mask(5)), 40);
+        // in 7 minutes: 0 then 1 releases 10 * 5 + 5 * 2, 1 then 0 only 5 * 4 + 10 * 1
+        assert_eq!(valves.best_by_mask(7), vec![0, 50, 20, 60]);
+    }
+}
@@ -3,10 +3,10 @@
 // nothing major, I've changed the pieces to be references instead of copies.
 // had to refactor GridPos to i64 as numbers are getting bigger here.
 
-use std::collections::HashMap;
 use log::{debug, info};
 use once_cell::sync::Lazy;
 use adventofcode::grid::GridPos;
+use adventofcode::memo::Memo;
 use adventofcode::Solver;
 
 const MAX_ROCKS_P1: u64 = 2022;
@@ -25,7 +25,7 @@ pub(crate) struct Solution {
     rocks: u64,
     max_height: i64,
     chamber: Vec<Vec<u8>>,
-    status: HashMap<CacheKey, (u64, i64)>,
+    status: Memo<CacheKey, (u64, i64)>,
 }
 
 impl Solution {
@@ -38,7 +38,7 @@ impl Solution {
             rocks: 0,
             max_height: 0,
             chamber: Vec::new(),
-            status: HashMap::new(),
+            status: Memo::new(),
         }
     }
 }
@@ -148,18 +148,17 @@ impl Solution {
             }
         }
         let cache_key = CacheKey(self.wind_pos, piece, column_status.clone());
-        if !self.status.contains_key(&cache_key) {
+        let Some(&(old_rocks, old_height)) = self.status.get(&cache_key) else {
             self.status.insert(cache_key, (self.rocks, self.max_height));
             return;
-        }
+        };
 
-        let (old_rocks, old_height) = &self.status[&cache_key];
         debug!("Found cycle: {} => {}: {:?}", old_rocks, self.rocks, cache_key);
         if self.height1.is_none() {
-            self.height1 = Some(self.calculate_height(MAX_ROCKS_P1, *old_rocks, *old_height));
+            self.height1 = Some(self.calculate_height(MAX_ROCKS_P1, old_rocks, old_height));
         }
         if self.height2.is_none() {
-            self.height2 = Some(self.calculate_height(MAX_ROCKS_P2, *old_rocks, *old_height));
+            self.height2 = Some(self.calculate_height(MAX_ROCKS_P2, old_rocks, old_height));
         }
     }
 
@@ -209,6 +208,7 @@ impl Solver for Solution {
                 debug!("{} rocks dropped", self.rocks);
             }
         }
+        self.status.report("Chamber states");
         info!("[1] Chamber height: {}", self.height1.unwrap());
         info!("[2] Chamber height: {}", self.height2.unwrap());
         Some((self.height1.unwrap().to_string(), self.height2.unwrap().to_string()))
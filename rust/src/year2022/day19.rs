@@ -4,9 +4,11 @@
 // used slices for some variables - finally got some understanding of those.
 // Interesting enough, even in debug times are quite fast (69 vs 576 sec for test input, 5 vs 42 sec
 // for challenge input => release drops times to 12 & 0.7).
+// Later: dropped the cache for branch and bound (prune when even a geode robot per minute can't beat
+// the best so far) and one thread per blueprint.
 
-use std::collections::HashMap;
 use std::str::FromStr;
+use std::thread;
 use std::time::SystemTime;
 use log::{debug, info};
 use once_cell::sync::Lazy;
@@ -23,109 +25,96 @@ impl Solution {
             blueprints: Vec::new(),
         }
     }
+}
 
-    fn find_max_geodes(&self, stats: &mut Stats, bp: &Blueprint, minutes_left: i32, robots: [i32; 4], materials: [i32; 4]) -> i32 {
-        stats.calls += 1;
-        if stats.calls % 500000 == 0 {
-            println!("{} calls, {} cache hits", stats.calls, stats.cache_hits);
-        }
-        assert!(minutes_left > 0);
-        // if we're at time, just return what we have
-        if minutes_left == 0 {
-            return  materials[GEODE];
-        }
-        // have we seen this status?
-        let cache_key = CacheKey (
-            minutes_left,
-            robots.clone(),
-            materials[ORE].min(bp.max_materials[ORE] * minutes_left),
-            materials[CLAY].min(bp.max_materials[CLAY] * minutes_left),
-            materials[OBSIDIAN].min(bp.max_materials[OBSIDIAN] * minutes_left),
-            materials[GEODE]
-        );
-        if stats.cache.contains_key(&cache_key) {
-            stats.cache_hits += 1;
-            return stats.cache[&cache_key];
+/// Branch and bound search: instead of caching states, each branch is dropped as soon as its
+/// optimistic estimate (building a geode robot every minute left) can't beat the best found so far.
+/// Robots of a type are capped at the maximum that can be spent of that material in a minute, and
+/// the search jumps straight to the next robot to build instead of simulating idle minutes.
+fn find_max_geodes(stats: &mut Stats, bp: &Blueprint, minutes_left: i32, robots: [i32; 4], materials: [i32; 4], best: &mut i32) {
+    stats.calls += 1;
+    // what we'll have at the end without building anything else
+    let idle_geodes = materials[GEODE] + (robots[GEODE] * minutes_left);
+    *best = (*best).max(idle_geodes);
+    if idle_geodes + minutes_left * (minutes_left - 1) / 2 <= *best {
+        stats.pruned += 1;
+        return;
+    }
+    // geode robots first: finds good solutions early, so the bound prunes more
+    for bot_type in MATERIALS.iter().rev().copied() {
+        let recipe = &bp.recipes[bot_type];
+        if bot_type != GEODE && robots[bot_type] >= bp.max_materials[bot_type] {
+            // culling - building robots of a type over the maximum consumption of a material is not necessary
+            continue;
         }
-        // start with maximum that can be produced by the current status
-        let mut max_geodes = materials[GEODE] + (robots[GEODE] * minutes_left);
-        for bot_type in MATERIALS {
-            let recipe = &bp.recipes[bot_type];
-            if bot_type != GEODE && robots[bot_type] >= bp.max_materials[bot_type] {
-                // culling - building robots of a type over the maximum consumption of a material is not necessary
-                continue;
-            }
-            let mut time_needed = 1;
-            for mat in MATERIALS {
-                if robots[mat] == 0 && recipe[mat] > 0 {
-                    // can't produce
-                    time_needed = -1;
-                    break;
-                }
-                if recipe[mat] == 0 {
-                    continue;
-                }
-                // count how many more materials I need and then divide by existing robot - always rounding up
-                // int divide a/b rounding up -> (a + b - 1) / b
-                let rounds = (recipe[mat] - materials[mat] + robots[mat] - 1) / robots[mat] + 1;
-                time_needed = time_needed.max(rounds);
+        let mut time_needed = 1;
+        for mat in MATERIALS {
+            if robots[mat] == 0 && recipe[mat] > 0 {
+                // can't produce
+                time_needed = -1;
+                break;
             }
-            if time_needed > 0 && minutes_left - time_needed > 0 {
-                let mut new_robots = robots.clone();
-                new_robots[bot_type] += 1;
-                let new_materials = [
-                    materials[ORE] + (robots[ORE] * time_needed) - recipe[ORE],
-                    materials[CLAY] + (robots[CLAY] * time_needed) - recipe[CLAY],
-                    materials[OBSIDIAN] + (robots[OBSIDIAN] * time_needed) - recipe[OBSIDIAN],
-                    materials[GEODE] + (robots[GEODE] * time_needed) - recipe[GEODE],
-                ];
-                max_geodes = max_geodes.max(
-                    self.find_max_geodes(stats, bp, minutes_left - time_needed, new_robots, new_materials)
-                );
+            if recipe[mat] == 0 {
+                continue;
             }
+            // count how many more materials I need and then divide by existing robot - always rounding up
+            // int divide a/b rounding up -> (a + b - 1) / b
+            let rounds = (recipe[mat] - materials[mat] + robots[mat] - 1) / robots[mat] + 1;
+            time_needed = time_needed.max(rounds);
+        }
+        if time_needed > 0 && minutes_left - time_needed > 0 {
+            let mut new_robots = robots;
+            new_robots[bot_type] += 1;
+            let new_materials = [
+                materials[ORE] + (robots[ORE] * time_needed) - recipe[ORE],
+                materials[CLAY] + (robots[CLAY] * time_needed) - recipe[CLAY],
+                materials[OBSIDIAN] + (robots[OBSIDIAN] * time_needed) - recipe[OBSIDIAN],
+                materials[GEODE] + (robots[GEODE] * time_needed) - recipe[GEODE],
+            ];
+            find_max_geodes(stats, bp, minutes_left - time_needed, new_robots, new_materials, best);
         }
-
-        stats.cache.insert(cache_key, max_geodes);
-        max_geodes
     }
 }
 
+fn evaluate(bp: &Blueprint, minutes: i32) -> (i32, Stats) {
+    let mut stats = Stats { calls: 0, pruned: 0, elapsed: 0.0 };
+    let mut best = 0;
+    let t0 = SystemTime::now();
+    find_max_geodes(&mut stats, bp, minutes, [1, 0, 0, 0], [0, 0, 0, 0], &mut best);
+    stats.elapsed = SystemTime::now().duration_since(t0).unwrap().as_secs_f32();
+    (best, stats)
+}
+
 impl Solver for Solution {
     fn parse(&mut self, line: &str) {
         self.blueprints.push(Blueprint::parse(line));
     }
 
     fn solve(&mut self) -> Option<(String, String)> {
+        // every blueprint is independent: one thread each, for both parts
+        let (part1, part2) = thread::scope(|scope| {
+            let part1: Vec<_> = self.blueprints.iter()
+                .map(|bp| scope.spawn(move || evaluate(bp, 24)))
+                .collect();
+            let part2: Vec<_> = self.blueprints.iter().take(3)
+                .map(|bp| scope.spawn(move || evaluate(bp, 32)))
+                .collect();
+            (part1.into_iter().map(|h| h.join().unwrap()).collect::<Vec<_>>(),
+             part2.into_iter().map(|h| h.join().unwrap()).collect::<Vec<_>>())
+        });
+
         let mut total1 = 0;
-        let mut total2 = 1;
-        let mut part2 = 0;
-        for bp in &self.blueprints {
-            debug!("Finding max geodes for blueprint {} => {:?}", bp.id, bp.recipes);
-            let robots = [1, 0, 0, 0];
-            let material = [0, 0, 0, 0];
-            let mut stats = Stats { calls: 0, cache_hits: 0, cache: HashMap::new() };
-            let t0 = SystemTime::now();
-            let max_geodes = self.find_max_geodes(&mut stats, bp, 24, robots, material);
+        for (bp, (max_geodes, stats)) in self.blueprints.iter().zip(&part1) {
             total1 += max_geodes * bp.id;
-            let t1 = SystemTime::now();
-            let diff_sec = t1.duration_since(t0).unwrap().as_secs_f32();
-            let iter_time = 1000000.0 * diff_sec / stats.calls as f32;
-            debug!("[part 1] Blueprint {} => {max_geodes} ({total1}) [{:.3}sec {} total calls / {:.3} us/call / {} cache hits]",
-                     bp.id, diff_sec, stats.calls, iter_time, stats.cache_hits);
-
-            if part2 < 3 {
-                part2 += 1;
-                let mut stats = Stats { calls: 0, cache_hits: 0, cache: HashMap::new() };
-                let t0 = SystemTime::now();
-                let max_geodes = self.find_max_geodes(&mut stats, bp, 32, robots, material);
-                total2 *= max_geodes;
-                let t1 = SystemTime::now();
-                let diff_sec = t1.duration_since(t0).unwrap().as_secs_f32();
-                let iter_time = 1000000.0 * diff_sec / stats.calls as f32;
-                debug!("[part 2] Blueprint {} => {max_geodes} ({total2}) [{:.3}sec {} total calls / {:.3} us/call / {} cache hits]",
-                         bp.id, diff_sec, stats.calls, iter_time, stats.cache_hits);
-            }
+            stats.report(1, bp, *max_geodes);
+        }
+        Stats::summary(1, &part1);
+        let mut total2 = 1;
+        for (bp, (max_geodes, stats)) in self.blueprints.iter().zip(&part2) {
+            total2 *= max_geodes;
+            stats.report(2, bp, *max_geodes);
         }
+        Stats::summary(2, &part2);
         info!("[1] result is {total1}");
         info!("[2] result is {total2}");
         Some((total1.to_string(), total2.to_string()))
@@ -191,11 +180,25 @@ impl Blueprint {
     }
 }
 
-#[derive(Eq, Hash, PartialEq)]
-struct CacheKey(i32, [i32; 4], i32, i32, i32, i32);
-
 struct Stats {
-    calls: u32,
-    cache: HashMap<CacheKey, i32>,
-    cache_hits: u32,
-}
\ No newline at end of file
+    calls: u64,
+    pruned: u64,
+    elapsed: f32,
+}
+
+impl Stats {
+    fn report(&self, part: u32, bp: &Blueprint, max_geodes: i32) {
+        let iter_time = 1000000.0 * self.elapsed / self.calls as f32;
+        debug!("[part {part}] Blueprint {} => {max_geodes} [{:.3}sec {} total calls / {:.3} us/call / {} pruned]",
+                 bp.id, self.elapsed, self.calls, iter_time, self.pruned);
+    }
+
+    /// totals over all the blueprints of a part (elapsed is summed over the threads)
+    fn summary(part: u32, results: &[(i32, Stats)]) {
+        let calls: u64 = results.iter().map(|(_, s)| s.calls).sum();
+        let pruned: u64 = results.iter().map(|(_, s)| s.pruned).sum();
+        let elapsed: f32 = results.iter().map(|(_, s)| s.elapsed).sum();
+        info!("[part {part}] {} blueprints: {calls} calls / {pruned} pruned [{elapsed:.3}sec in all threads]",
+              results.len());
+    }
+}
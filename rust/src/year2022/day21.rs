@@ -1,13 +1,17 @@
 // What did I learn?
 // A bit of Result usage to handle "errors" (= using error to break from recursive calls)
+// Later moved to exact expressions (expr module): f64 could round on big inputs, now part 2 solves
+// the linear equation over big rationals.
 
 use std::collections::HashMap;
 use std::str::FromStr;
 use log::{debug, info};
+use num::BigRational;
 
 use once_cell::sync::Lazy;
 use regex::{Captures, Regex};
 
+use adventofcode::expr::{solve, Expr, ExprError, Op};
 use adventofcode::Solver;
 
 pub(crate) struct Solution {
@@ -21,66 +25,29 @@ impl Solution {
         }
     }
 
-    fn calculate(&self, monkey: &Monkey, part2: bool) -> Result<f64, &str>{
+    /// builds the expression tree for the given monkey; in part 2 the human is the unknown
+    fn expression(&self, monkey: &Monkey, part2: bool) -> Expr {
         if part2 && monkey.name == HUMAN {
-            return Err("Hooman do not know number");
+            return Expr::Unknown;
         }
-
-        let result = match &monkey.action {
-            Action::Number(num) => *num,
-            Action::Calculus(left, op, right) => {
-                let left_res = self.calculate(&self.monkeys[left.as_str()], part2)?;
-                let right_res = self.calculate(&self.monkeys[right.as_str()], part2)?;
-                match op {
-                    Op::Sum => left_res + right_res,
-                    Op::Subtract => left_res - right_res,
-                    Op::Multiply => left_res * right_res,
-                    Op::Divide => left_res / right_res,
-                }
-            }
-        };
-        Ok(result)
-    }
-
-    fn balance(&self, m: &Monkey) -> f64 {
-        let mut monkey = m;
-        // which branch is the one missing data?
-        let mut balance = None;
-        let mut value;
-        while monkey.name != HUMAN {
-            let Action::Calculus(m_left, op, m_right) = &monkey.action else {
-                panic!("Monkey Action is not a calculation: {} => {:?}", monkey.name, monkey.action);
-            };
-            let mut human = m_right;
-            match self.calculate(&self.monkeys[m_left], true) {
-                Ok(val) => { value = Some(val); },
-                Err(_) => {
-                    human = m_left;
-                    // this should not fail
-                    value = Some(self.calculate(&self.monkeys[m_right], true).unwrap());
-                }
-            }
-            if balance.is_none() {
-                debug!("Found root branch value: {}", value.unwrap());
-                balance = value;
-            } else {
-                balance = self.invert_op(balance.unwrap(), op, value.unwrap(), human == m_left);
-            }
-            monkey = &self.monkeys[human];
+        match &monkey.action {
+            Action::Number(num) => Expr::num(*num),
+            Action::Calculus(left, op, right) => Expr::binary(
+                self.expression(&self.monkeys[left.as_str()], part2),
+                *op,
+                self.expression(&self.monkeys[right.as_str()], part2),
+            ),
         }
-        balance.unwrap()
     }
 
-    fn invert_op(&self, balance: f64, op: &Op, value: f64, first: bool) -> Option<f64> {
-        let v_str = value.to_string();
-        debug!("Inverting {} {:?} {} = {balance}",
-                 if first { "x" } else { &v_str }, op, if first { &v_str } else { "x" });
-        match op {
-            Op::Sum => Some(balance - value),
-            Op::Subtract => Some(if first { balance + value } else { value - balance }),
-            Op::Multiply => Some(balance / value),
-            Op::Divide => Some(if first { balance * value } else { value / balance }),
-        }
+    fn balance(&self, root: &Monkey) -> Result<BigRational, ExprError> {
+        let Action::Calculus(left, _, right) = &root.action else {
+            panic!("Root action is not a calculation: {:?}", root.action);
+        };
+        let lhs = self.expression(&self.monkeys[left.as_str()], true);
+        let rhs = self.expression(&self.monkeys[right.as_str()], true);
+        debug!("Human is on the {} branch of root", if lhs.contains_unknown() { "left" } else { "right" });
+        solve(&lhs, &rhs)
     }
 }
 
@@ -95,7 +62,7 @@ impl Solver for Solution {
             let m = Monkey::with_op(name, captures);
             self.monkeys.insert(m.name.clone(), m);
         } else {
-            let m = Monkey::with_num(name, f64::from_str(op).unwrap());
+            let m = Monkey::with_num(name, i64::from_str(op).unwrap());
             self.monkeys.insert(m.name.clone(), m);
         }
 
@@ -105,39 +72,32 @@ impl Solver for Solution {
         assert!(self.monkeys.contains_key(ROOT), "Missing root in monkeys?");
 
         // part 1 - must not fail
-        let result = self.calculate(&self.monkeys[ROOT], false).unwrap();
+        let result = self.expression(&self.monkeys[ROOT], false).eval().unwrap();
         info!("[1] Result is {result}");
 
         // part 2
-        let value = self.balance(&self.monkeys[ROOT]);
+        let value = match self.balance(&self.monkeys[ROOT]) {
+            Ok(value) => value,
+            Err(err) => panic!("Can't find the human number: {err}"),
+        };
         info!("[2] HUMN {value}");
         Some((result.to_string(), value.to_string()))
     }
 }
 
-#[derive(Debug)]
-enum Op {
-    Sum,
-    Subtract,
-    Multiply,
-    Divide,
-}
-
-impl Op {
-    fn parse(text: &str) -> Op {
-        match text {
-            "+" => Op::Sum,
-            "-" => Op::Subtract,
-            "*" => Op::Multiply,
-            "/" => Op::Divide,
-            _ => panic!("Invalid operation: {text}"),
-        }
+fn parse_op(text: &str) -> Op {
+    match text {
+        "+" => Op::Add,
+        "-" => Op::Sub,
+        "*" => Op::Mul,
+        "/" => Op::Div,
+        _ => panic!("Invalid operation: {text}"),
     }
 }
 
 #[derive(Debug)]
 enum Action {
-    Number(f64),
+    Number(i64),
     Calculus(String, Op, String),
 }
 
@@ -150,7 +110,7 @@ impl Monkey {
     fn with_op(name: &str, op: Captures) -> Monkey {
         let action = Action::Calculus(
             String::from(&op[1]),
-            Op::parse(&op[2]),
+            parse_op(&op[2]),
             String::from(&op[3]),
         );
         Monkey {
@@ -159,7 +119,7 @@ impl Monkey {
         }
     }
 
-    fn with_num(name: &str, num: f64) -> Monkey {
+    fn with_num(name: &str, num: i64) -> Monkey {
         Monkey {
             name: String::from(name),
             action: Action::Number(num),
@@ -2,8 +2,10 @@
 // quite straight-forward conversion.
 // A lot of headaches flipping between i64 and usize (as many indexing only take usize).
 // Used a slice for known-length data.
+// Part 2 used to need the cube size and face connections written by hand at the end of the input
+// (the python version still reads them, here the `CUBE` lines are skipped); now the net is folded
+// in 3D to find them (works for any of the 11 cube nets).
 
-use std::str::FromStr;
 use std::usize;
 use log::{debug, info};
 use adventofcode::Solver;
@@ -25,6 +27,13 @@ const DIRS: [(i64, i64); 4] = [
 ];
 const DIR_TEXT: [char; 4] = ['>', 'v', '<', '^'];
 
+/// a direction in 3D space, one of the 6 unit vectors
+type Vec3 = [i64; 3];
+
+fn neg(v: Vec3) -> Vec3 {
+    [-v[0], -v[1], -v[2]]
+}
+
 fn turn(dir: usize, val: i64) -> usize {
     let mut v = dir as i64 + val;
     let len = DIRS.len() as i64;
@@ -74,21 +83,79 @@ impl Solution {
         debug!("Found {} faces", face_id);
     }
 
-    fn add_facing(&mut self, line: &str) {
-        let face_id = usize::from_str(&line[0..1]).unwrap() - 1;
-        let mut parts = line[2..].split(",");
-        let mut facing_id = 0;
-        while facing_id < 4 {
-            self.faces[face_id].facing[facing_id].0 = usize::from_str(parts.next().unwrap()).unwrap();
-            self.faces[face_id].facing[facing_id].1 = match parts.next().unwrap() {
-                "U" => 'U',
-                "L" => 'L',
-                "R" => 'R',
-                "=" => '=',
-                "None" => ' ',
-                _ => panic!("Unrecognized facing in {}", line),
-            };
-            facing_id += 1;
+    fn find_cube_size(&mut self) {
+        // the six faces cover the whole map, each is size x size
+        let area = self.map.iter()
+            .map(|row| row.iter().filter(|ch| **ch != ' ').count())
+            .sum::<usize>();
+        self.cube_size = (1..).find(|size| size * size * 6 >= area).unwrap();
+        assert_eq!(self.cube_size * self.cube_size * 6, area, "Map is not a cube net");
+        debug!("Cube size {}", self.cube_size);
+    }
+
+    fn face_at(&self, row: i64, col: i64) -> Option<usize> {
+        if row < 0 || col < 0 || !self.in_map(row as usize, col as usize) {
+            return None;
+        }
+        self.faces.iter().position(|f| f.contains(row as usize, col as usize))
+    }
+
+    /// Folds the net into a cube: walking the net from the first face, each face gets the 3D
+    /// orientation of its outward normal and of its "right" and "down" directions on the map.
+    /// Leaving a face towards direction v takes to the face whose normal is v, and there one
+    /// moves "into" the cube side, i.e. opposite the normal of the face just left.
+    fn fold_cube(&mut self) {
+        // (normal, right, down) for each face
+        let mut frames: [Option<(Vec3, Vec3, Vec3)>; 6] = [None; 6];
+        frames[0] = Some(([0, 0, 1], [1, 0, 0], [0, 1, 0]));
+        let mut queue = vec![0];
+        while let Some(id) = queue.pop() {
+            let (normal, right, down) = frames[id].unwrap();
+            let face = &self.faces[id];
+            for (dir, (dr, dc)) in DIRS.iter().enumerate() {
+                let next_row = face.row as i64 + dr * self.cube_size as i64;
+                let next_col = face.col as i64 + dc * self.cube_size as i64;
+                let Some(next) = self.face_at(next_row, next_col) else {
+                    continue;
+                };
+                if frames[next].is_some() {
+                    continue;
+                }
+                frames[next] = Some(match dir {
+                    0 => (right, neg(normal), down),
+                    1 => (down, right, neg(normal)),
+                    2 => (neg(right), normal, down),
+                    _ => (neg(down), right, normal),
+                });
+                queue.push(next);
+            }
+        }
+
+        for id in 0..self.faces.len() {
+            let (normal, right, down) = frames[id].expect("Face not connected to the net");
+            let exits = [right, down, neg(right), neg(down)];
+            for (dir, exit) in exits.iter().enumerate() {
+                let next = frames.iter().position(|f| f.unwrap().0 == *exit).unwrap();
+                let (_, next_right, next_down) = frames[next].unwrap();
+                let entering = neg(normal);
+                let new_dir = [next_right, next_down, neg(next_right), neg(next_down)].iter()
+                    .position(|v| *v == entering)
+                    .unwrap();
+                let face = &self.faces[id];
+                let adjacent = self.face_at(
+                    face.row as i64 + DIRS[dir].0 * self.cube_size as i64,
+                    face.col as i64 + DIRS[dir].1 * self.cube_size as i64,
+                ) == Some(next);
+                let rotate = match (new_dir + 4 - dir) % 4 {
+                    0 if adjacent => ' ',
+                    0 => '=',
+                    1 => 'R',
+                    2 => 'U',
+                    _ => 'L',
+                };
+                self.faces[id].facing[dir] = (next + 1, rotate);
+            }
+            debug!("Face {} connections: {:?}", id + 1, self.faces[id].facing);
         }
     }
 
@@ -253,12 +320,7 @@ impl Solver for Solution {
         }
         if self.is_map {
             self.add_to_map(line);
-        } else if line.starts_with("CUBE SIZE ") {
-            self.cube_size = usize::from_str(&line[10..]).unwrap();
-            self.find_faces()
-        } else if line.starts_with("CUBE FACE ") {
-            self.add_facing(&line[10..]);
-        } else {
+        } else if !line.starts_with("CUBE ") {
             self.parse_path(line);
         }
 
@@ -277,6 +339,9 @@ impl Solver for Solution {
         let password1 = (pos.0 + 1) * 1000 + (pos.1 + 1) * 4 + pos.2;
         info!("[1] final position: {:?} => password {password1}", pos);
 
+        self.find_cube_size();
+        self.find_faces();
+        self.fold_cube();
         let mut pos = (0, self.map[0].iter().position(|ch| *ch == '.').unwrap(), 0);
         debug!("==> Cube walk: starting position: {:?}", pos);
         for act in &self.path {
@@ -328,24 +393,28 @@ impl Face {
             (0, 'L') => (self.row + self.size, self.col + pos.0, turn(dir, -1)),
             // crossing from the right to the right border (flipping)
             (0, 'U') => (self.row + self.size - pos.0, self.col + self.size, turn(dir, 2)),
+            // right to left - no change in dir
+            (0, '=') => (self.row + pos.0, self.col, dir),
             // crossing from the bottom to the right border
             (1, 'R') => (self.row + pos.1, self.col + self.size, turn(dir, 1)),
             // crossing from the bottom to the left border
-            (1, 'L') => (self.row + pos.1, self.col, turn(dir, -1)),
+            (1, 'L') => (self.row + self.size - pos.1, self.col, turn(dir, -1)),
             // crossing from the bottom to the bottom border
             (1, 'U') => (self.row + self.size, self.col + self.size - pos.1, turn(dir, 2)),
             // bottom to top - no change in dir
             (1, '=') => (self.row, self.col + pos.1, dir),
             // crossing from the left to the bottom border
-            (2, 'R') => (self.row + self.size, self.col + pos.0, turn(dir, 1)),
+            (2, 'R') => (self.row + self.size, self.col + self.size - pos.0, turn(dir, 1)),
             // crossing from the left to the top border
             (2, 'L') => (self.row, self.col + pos.0, turn(dir, -1)),
             // crossing from the left to the left border
             (2, 'U') => (self.row + self.size - pos.0, self.col, turn(dir, 2)),
+            // left to right - no change in dir
+            (2, '=') => (self.row + pos.0, self.col + self.size, dir),
             // crossing from the top to the right border
             (3, 'R') => (self.row + pos.1, self.col, turn(dir, 1)),
             // crossing from the top to the left border
-            (3, 'L') => (self.row + pos.1, self.col + self.size, turn(dir, -1)),
+            (3, 'L') => (self.row + self.size - pos.1, self.col + self.size, turn(dir, -1)),
             // crossing from the top to the top border
             (3, 'U') => (self.row, self.col + self.size - pos.1, turn(dir, 2)),
             // top to bottom - no change in dir
@@ -358,4 +427,111 @@ impl Face {
 enum Move {
     Walk(u32),
     Turn(char),
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_facing(text: &str) -> [(usize, char); 4] {
+        // same format the connections were written by hand in the input: id,rotation x 4
+        let parts: Vec<&str> = text.split(",").collect();
+        let mut facing = [(0, ' '); 4];
+        for (i, f) in facing.iter_mut().enumerate() {
+            f.0 = parts[i * 2].parse().unwrap();
+            f.1 = match parts[i * 2 + 1] {
+                "None" => ' ',
+                other => other.chars().next().unwrap(),
+            };
+        }
+        facing
+    }
+
+    fn fold(map: &[&str]) -> Solution {
+        let mut solution = Solution::new();
+        map.iter().for_each(|line| solution.parse(line));
+        solution.find_cube_size();
+        solution.find_faces();
+        solution.fold_cube();
+        solution
+    }
+
+    fn assert_faces(solution: &Solution, expected: &[&str; 6]) {
+        for (i, text) in expected.iter().enumerate() {
+            assert_eq!(solution.faces[i].facing, parse_facing(text), "face {}", i + 1);
+        }
+    }
+
+    const EXAMPLE: [&str; 12] = [
+        "        ...#",
+        "        .#..",
+        "        #...",
+        "        ....",
+        "...#.......#",
+        "........#...",
+        "..#....#....",
+        "..........#.",
+        "        ...#....",
+        "        .....#..",
+        "        .#......",
+        "        ......#.",
+    ];
+
+    #[test]
+    fn test_fold_example() {
+        let solution = fold(&EXAMPLE);
+        assert_eq!(solution.cube_size, 4);
+        assert_faces(&solution, &[
+            "6,U,4,None,3,L,2,U",
+            "3,None,5,U,6,R,1,U",
+            "4,None,5,L,2,None,1,R",
+            "6,R,5,None,3,None,1,None",
+            "6,None,2,U,3,R,4,None",
+            // the hand-written table had 2,U here: see test_cross_edges
+            "1,U,2,L,5,None,4,L",
+        ]);
+    }
+
+    #[test]
+    fn test_fold_input_net() {
+        // the layout all real inputs seem to share, shrunk to size 2
+        let solution = fold(&["  ....", "  ....", "  ..", "  ..", "....", "....", "..", ".."]);
+        assert_eq!(solution.cube_size, 2);
+        assert_faces(&solution, &[
+            "2,None,3,None,4,U,6,R",
+            "5,U,3,R,1,None,6,=",
+            "2,L,5,None,4,L,1,None",
+            "5,None,6,None,1,U,3,R",
+            "2,U,6,R,4,None,3,None",
+            "5,L,2,=,1,L,4,None",
+        ]);
+    }
+
+    #[test]
+    fn test_cross_edges() {
+        let solution = fold(&EXAMPLE);
+        // (row, col, facing) before and after the step, 0-based
+        let step = |pos: (usize, usize, usize)| solution.cube_walk(pos);
+        // the crossing shown in the puzzle: A at row 6, column 12 facing right => B at row 9, column 15
+        assert_eq!(step((5, 11, 0)), (8, 14, 1));
+        assert_eq!(step((8, 14, 3)), (5, 11, 2));
+        // 6 down => 2: the bottom left corner of 6 touches the bottom left corner of 2 (both
+        // touch 5), so it comes in from the left side of 2, at its bottom, moving right
+        assert_eq!(step((11, 12, 1)), (7, 0, 0));
+        assert_eq!(step((11, 15, 1)), (4, 0, 0));
+        assert_eq!(step((7, 0, 2)), (11, 12, 3));
+        // every step over an edge, turned around, goes back to the same tile
+        for face in &solution.faces {
+            for k in 0..=face.size {
+                let borders = [
+                    (face.row + k, face.col + face.size), (face.row + face.size, face.col + k),
+                    (face.row + k, face.col), (face.row, face.col + k),
+                ];
+                for (dir, (row, col)) in borders.iter().enumerate() {
+                    let (r, c, d) = step((*row, *col, dir));
+                    let (r, c, _) = step((r, c, turn(d, 2)));
+                    assert_eq!((r, c), (*row, *col), "face {} facing {}", face.id, DIR_TEXT[dir]);
+                }
+            }
+        }
+    }
+}
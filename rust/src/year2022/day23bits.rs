@@ -0,0 +1,212 @@
+// Bitset version of day23 (day23 is kept as the reference implementation):
+// - every row of the grove is a row of bits, 64 columns per word, so a whole word of elves checks
+//   its neighbours and proposes a move with a handful of and/or/shift operations
+// - the grove grows (one row or one word of columns at a time) whenever an elf reaches the border
+// - two elves can only propose the same spot when they come from opposite sides (N/S or W/E), so
+//   collisions are found comparing the proposals two rows or two columns apart
+
+use log::{debug, info};
+use adventofcode::Solver;
+
+pub(crate) struct Solution {
+    grove: Grove,
+}
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            grove: Grove { words: 0, rows: Vec::new() },
+        }
+    }
+}
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        if self.grove.words == 0 {
+            self.grove.words = line.len() / 64 + 1;
+        }
+        let mut row = vec![0; self.grove.words];
+        line.match_indices('#').for_each(|(col, _)| row[col / 64] |= 1 << (col % 64));
+        self.grove.rows.push(row);
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        debug!("Will move around {} elves", self.grove.count());
+        let mut area = 0;
+        let mut rounds = 0;
+        loop {
+            let moves = self.grove.round(rounds);
+            rounds += 1;
+            if rounds == 10 {
+                area = self.grove.empty_area();
+            }
+            if !moves {
+                break;
+            }
+        }
+
+        info!("[1] Empty area is {area}");
+        info!("[2] Round {rounds} => no moves");
+        Some((area.to_string(), rounds.to_string()))
+    }
+}
+
+// proposal directions, in the order of the first round
+const NORTH: usize = 0;
+const SOUTH: usize = 1;
+const WEST: usize = 2;
+const EAST: usize = 3;
+
+#[derive(Clone)]
+struct Grove {
+    /// words of 64 columns in each row
+    words: usize,
+    /// bit `c % 64` of word `c / 64` is set when there's an elf in column `c`
+    rows: Vec<Vec<u64>>,
+}
+
+/// every bit moved one column east (col + 1)
+fn east(row: &[u64]) -> Vec<u64> {
+    (0..row.len())
+        .map(|w| (row[w] << 1) | if w > 0 { row[w - 1] >> 63 } else { 0 })
+        .collect()
+}
+
+/// every bit moved one column west (col - 1)
+fn west(row: &[u64]) -> Vec<u64> {
+    (0..row.len())
+        .map(|w| (row[w] >> 1) | if w + 1 < row.len() { row[w + 1] << 63 } else { 0 })
+        .collect()
+}
+
+impl Grove {
+    fn row(&self, r: isize) -> Vec<u64> {
+        if r < 0 || r as usize >= self.rows.len() {
+            return vec![0; self.words];
+        }
+        self.rows[r as usize].clone()
+    }
+
+    /// keeps an empty border all around the elves, so nobody can move outside the grove
+    fn grow(&mut self) {
+        if self.rows[0].iter().any(|w| *w != 0) {
+            self.rows.insert(0, vec![0; self.words]);
+        }
+        if self.rows[self.rows.len() - 1].iter().any(|w| *w != 0) {
+            self.rows.push(vec![0; self.words]);
+        }
+        if self.rows.iter().any(|row| row[0] & 1 != 0) {
+            self.rows.iter_mut().for_each(|row| row.insert(0, 0));
+            self.words += 1;
+        }
+        if self.rows.iter().any(|row| row[self.words - 1] >> 63 != 0) {
+            self.rows.iter_mut().for_each(|row| row.push(0));
+            self.words += 1;
+        }
+    }
+
+    /// plays one round, returns true if any elf moved
+    fn round(&mut self, round: usize) -> bool {
+        self.grow();
+        let height = self.rows.len() as isize;
+
+        // proposals[r][dir]: elves in row r proposing to move in dir
+        let proposals: Vec<[Vec<u64>; 4]> = (0..height)
+            .map(|r| {
+                let (n, c, s) = (self.row(r - 1), self.row(r), self.row(r + 1));
+                let (n_e, n_w, c_e, c_w, s_e, s_w) = (east(&n), west(&n), east(&c), west(&c), east(&s), west(&s));
+                let mut props: [Vec<u64>; 4] = Default::default();
+                props.iter_mut().for_each(|p| *p = vec![0; self.words]);
+                for w in 0..self.words {
+                    // something in the three cells on each side (east() brings the west neighbours here)
+                    let taken = [n[w] | n_e[w] | n_w[w], s[w] | s_e[w] | s_w[w],
+                        n_e[w] | c_e[w] | s_e[w], n_w[w] | c_w[w] | s_w[w]];
+                    // elves with nobody around stay put
+                    let mut todo = c[w] & (taken[NORTH] | taken[SOUTH] | taken[WEST] | taken[EAST]);
+                    for i in 0..4 {
+                        let dir = (round + i) % 4;
+                        props[dir][w] = todo & !taken[dir];
+                        todo &= !props[dir][w];
+                    }
+                }
+                props
+            })
+            .collect();
+        let proposal = |r: isize, dir: usize| -> Vec<u64> {
+            if r < 0 || r >= height {
+                return vec![0; self.words];
+            }
+            proposals[r as usize][dir].clone()
+        };
+
+        // moves that don't collide with the elf coming from the opposite side
+        let allowed: Vec<[Vec<u64>; 4]> = (0..height)
+            .map(|r| {
+                let (south_to, north_to) = (proposal(r - 2, SOUTH), proposal(r + 2, NORTH));
+                let east_to = east(&east(&proposal(r, EAST)));
+                let west_to = west(&west(&proposal(r, WEST)));
+                let props = &proposals[r as usize];
+                [
+                    (0..self.words).map(|w| props[NORTH][w] & !south_to[w]).collect(),
+                    (0..self.words).map(|w| props[SOUTH][w] & !north_to[w]).collect(),
+                    (0..self.words).map(|w| props[WEST][w] & !east_to[w]).collect(),
+                    (0..self.words).map(|w| props[EAST][w] & !west_to[w]).collect(),
+                ]
+            })
+            .collect();
+
+        let mut moved = false;
+        let rows = (0..height)
+            .map(|r| {
+                let mine = &allowed[r as usize];
+                let from_south = if r + 1 < height { allowed[r as usize + 1][NORTH].clone() } else { vec![0; self.words] };
+                let from_north = if r > 0 { allowed[r as usize - 1][SOUTH].clone() } else { vec![0; self.words] };
+                let from_east = west(&mine[WEST]);
+                let from_west = east(&mine[EAST]);
+                (0..self.words)
+                    .map(|w| {
+                        let leaving = mine[NORTH][w] | mine[SOUTH][w] | mine[WEST][w] | mine[EAST][w];
+                        moved |= leaving != 0;
+                        (self.rows[r as usize][w] & !leaving) | from_south[w] | from_north[w] | from_east[w] | from_west[w]
+                    })
+                    .collect()
+            })
+            .collect();
+        self.rows = rows;
+        moved
+    }
+
+    fn count(&self) -> u32 {
+        self.rows.iter().flatten().map(|w| w.count_ones()).sum()
+    }
+
+    /// empty ground in the smallest rectangle containing all the elves
+    fn empty_area(&self) -> i64 {
+        let used: Vec<usize> = (0..self.rows.len()).filter(|r| self.rows[*r].iter().any(|w| *w != 0)).collect();
+        let height = used[used.len() - 1] - used[0] + 1;
+        let occupied: Vec<u64> = (0..self.words)
+            .map(|w| self.rows.iter().fold(0, |acc, row| acc | row[w]))
+            .collect();
+        let first = occupied.iter().position(|w| *w != 0).unwrap();
+        let last = occupied.iter().rposition(|w| *w != 0).unwrap();
+        let left = first * 64 + occupied[first].trailing_zeros() as usize;
+        let right = last * 64 + 63 - occupied[last].leading_zeros() as usize;
+        (height * (right - left + 1)) as i64 - self.count() as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grow() {
+        let mut grove = Grove { words: 1, rows: vec![vec![1], vec![1 << 63]] };
+        grove.grow();
+        assert_eq!(grove.words, 3);
+        assert_eq!(grove.rows, vec![vec![0; 3], vec![0, 1, 0], vec![0, 1 << 63, 0], vec![0; 3]]);
+    }
+}
@@ -1,9 +1,12 @@
 // What did I learn?
 // mostly smooth sailing, some clones needed to make borrow checker happy
+// Later: blizzards repeat every lcm(width, height) minutes, so their positions are precomputed once
+// for the whole period as a bitset per row (64 columns per word), and the search moves the whole
+// frontier of reachable cells one minute at a time with shifts, instead of a BFS over (pos, t).
 
-use std::collections::{HashSet, VecDeque};
 use log::{debug, info};
 use adventofcode::grid::{GridPos, MOVE_D, MOVE_L, MOVE_R, MOVE_U};
+use adventofcode::math::lcm;
 use adventofcode::Solver;
 
 pub(crate) struct Solution {
@@ -12,7 +15,6 @@ pub(crate) struct Solution {
     entry: GridPos,
     exit: GridPos,
     blizzards: Vec<Blizzard>,
-    bliz_time: Vec<HashSet<GridPos>>,
 }
 
 impl Solution {
@@ -23,75 +25,14 @@ impl Solution {
             entry: GridPos::of(0, 0),
             exit: GridPos::of(0, 0),
             blizzards: Vec::new(),
-            bliz_time: Vec::new(),
         }
     }
 
-    fn blizzards_at_time(&mut self, t: i64) -> &HashSet<GridPos> {
-        if t < self.bliz_time.len() as i64 {
-            return &self.bliz_time[t as usize]
-        }
-        let bliz_pos: HashSet<GridPos> = self.blizzards.iter().map(|b| GridPos {
-                row: move_wrap(b.pos.row, b.dir.row * t, (1, self.height - 2)),
-                col: move_wrap(b.pos.col, b.dir.col * t, (1, self.width - 2)),
-            }).collect();
-        self.bliz_time.push(bliz_pos);
-        &self.bliz_time[t as usize]
-    }
-
-    fn find_path(&mut self, entry: GridPos, exit: GridPos, t: i64) -> i64 {
-        debug!("Finding path {entry} => {exit} starting at {t}");
-        let mut steps = VecDeque::from([(entry, t)]);
-        let mut visited = HashSet::new();
-        // need copies to avoid second borrow:
-        let map_entry = self.entry.clone();
-        let map_exit = self.exit.clone();
-        let map_width = self.width;
-        let map_height = self.height;
-        loop {
-            let (pos, t) = steps.pop_front().unwrap();
-            if pos == exit {
-                // one less - minutes starting at "0"
-                return t - 1;
-            }
-            if !visited.insert((pos.clone(), t)) {
-                continue;
-            }
-            let blizzards = self.blizzards_at_time(t);
-            for dir in &[MOVE_D, MOVE_R, MOVE_U, MOVE_L] {
-                let new_pos = pos.add(dir);
-                if blizzards.contains(&new_pos) {
-                    continue;
-                }
-                // check if it's entry or exit
-                let mut valid = new_pos == map_entry || new_pos == map_exit;
-                // check for bounds
-                valid |= (0 < new_pos.row && new_pos.row < map_height - 1) && (0 < new_pos.col && new_pos.col < map_width - 1);
-                if !valid {
-                    continue;
-                }
-                // println!("({}, {}), {t} -> ({}, {})", pos.row, pos.col, new_pos.row, new_pos.col);
-                steps.push_back((new_pos, t + 1));
-            }
-            // check if next round pos it's empty
-            if !blizzards.contains(&pos) {
-                // println!("({}, {}), {t} -> ({}, {}) [wait]", pos.row, pos.col, pos.row, pos.col);
-                steps.push_back((pos, t + 1));
-            }
-        }
-    }
-}
-
-fn move_wrap(val: i64, delta: i64, bounds: (i64, i64)) -> i64 {
-    let mut out = val + delta;
-    let w = bounds.1 - bounds.0 + 1;
-    while out < bounds.0 {
-        out += w;
-    }
-    while out > bounds.1 {
-        out -= w;
+    /// entry and exit are in the walls: returns the valley cell (row, col) right next to each
+    fn valley_cell(&self, pos: &GridPos) -> (usize, usize) {
+        let row = if pos.row == 0 { 0 } else { self.height - 3 };
+        (row as usize, pos.col as usize - 1)
     }
-    out
 }
 
 impl Solver for Solution {
@@ -114,11 +55,13 @@ impl Solver for Solution {
 
     fn solve(&mut self) -> Option<(String, String)> {
         debug!("Tracing path from {:?} => {:?}", self.entry, self.exit);
-        self.blizzards_at_time(0);
-        let t1 = self.find_path(self.entry.clone(), self.exit.clone(), 0);
+        let valley = Valley::new(self.width as usize - 2, self.height as usize - 2, &self.blizzards);
+        debug!("Blizzards repeat every {} minutes", valley.period);
+        let (entry, exit) = (self.valley_cell(&self.entry), self.valley_cell(&self.exit));
+        let t1 = valley.crossing(entry, exit, 0).expect("No way to the exit");
         info!("[1] Found exit in: {t1}");
-        let mut t2 = self.find_path(self.exit.clone(), self.entry.clone(), t1);
-        t2 = self.find_path(self.entry.clone(), self.exit.clone(), t2);
+        let mut t2 = valley.crossing(exit, entry, t1).expect("No way back to the entry");
+        t2 = valley.crossing(entry, exit, t2).expect("No way to the exit");
         info!("[2] Total time: {t2}");
         Some((t1.to_string(), t2.to_string()))
     }
@@ -143,4 +86,168 @@ impl Blizzard {
             dir,
         }
     }
-}
\ No newline at end of file
+}
+
+/// bitsets of any size, 64 bits per word
+fn words(bits: usize) -> Vec<u64> {
+    vec![0; bits.div_ceil(64)]
+}
+
+fn bit(bits: &[u64], i: usize) -> bool {
+    bits[i / 64] & (1 << (i % 64)) != 0
+}
+
+fn set_bit(bits: &mut [u64], i: usize) {
+    bits[i / 64] |= 1 << (i % 64);
+}
+
+/// every bit moved one column east (col + 1)
+fn east(row: &[u64]) -> Vec<u64> {
+    (0..row.len())
+        .map(|w| (row[w] << 1) | if w > 0 { row[w - 1] >> 63 } else { 0 })
+        .collect()
+}
+
+/// every bit moved one column west (col - 1)
+fn west(row: &[u64]) -> Vec<u64> {
+    (0..row.len())
+        .map(|w| (row[w] >> 1) | if w + 1 < row.len() { row[w + 1] << 63 } else { 0 })
+        .collect()
+}
+
+struct Valley {
+    width: usize,
+    height: usize,
+    period: usize,
+    /// occupied[t % period][row]: bit `col` is set if there's a blizzard in (row, col) at minute t
+    occupied: Vec<Vec<Vec<u64>>>,
+}
+
+impl Valley {
+    fn new(width: usize, height: usize, blizzards: &[Blizzard]) -> Valley {
+        let period = lcm(width as i64, height as i64) as usize;
+        // initial blizzards (valley coordinates, walls excluded): the ones moving left or right
+        // stay in their row, so they're a bitset of columns per row; the ones moving up or down
+        // are a bitset of rows per column. MOVE_U is row + 1, i.e. 'v' on the map.
+        let mut right = vec![words(width); height];
+        let mut left = vec![words(width); height];
+        let mut up = vec![words(height); width];
+        let mut down = vec![words(height); width];
+        for blizzard in blizzards {
+            let (row, col) = (blizzard.pos.row as usize - 1, blizzard.pos.col as usize - 1);
+            match blizzard.dir {
+                MOVE_R => set_bit(&mut right[row], col),
+                MOVE_L => set_bit(&mut left[row], col),
+                MOVE_U => set_bit(&mut up[col], row),
+                _ => set_bit(&mut down[col], row),
+            }
+        }
+        let occupied = (0..period)
+            .map(|t| (0..height)
+                .map(|row| {
+                    let mut bits = words(width);
+                    for col in 0..width {
+                        if bit(&right[row], (col + width - t % width) % width)
+                            || bit(&left[row], (col + t) % width)
+                            || bit(&up[col], (row + height - t % height) % height)
+                            || bit(&down[col], (row + t) % height) {
+                            set_bit(&mut bits, col);
+                        }
+                    }
+                    bits
+                })
+                .collect())
+            .collect();
+        Valley { width, height, period, occupied }
+    }
+
+    /// minute we step out of the valley from the `to` cell, leaving from the cell `from` at minute
+    /// `start`; waiting at the starting point (outside of the valley) is always safe.
+    /// Hence the cells reachable at t + period include the ones reachable at t (wait a period
+    /// first): if they stay the same over a whole period, `to` can't be reached.
+    fn crossing(&self, from: (usize, usize), to: (usize, usize), start: usize) -> Option<usize> {
+        // the last word of each row only keeps the columns in the valley
+        let mut mask = vec![u64::MAX; self.width.div_ceil(64)];
+        if !self.width.is_multiple_of(64) {
+            mask[self.width / 64] = (1 << (self.width % 64)) - 1;
+        }
+        let none = words(self.width);
+        let mut frontier = vec![none.clone(); self.height];
+        let mut previous = frontier.clone();
+        let mut t = start;
+        while !bit(&frontier[to.0], to.1) {
+            if (t - start).is_multiple_of(self.period) {
+                if t > start && frontier == previous {
+                    debug!("Nothing new reachable after minute {t}");
+                    return None;
+                }
+                previous = frontier.clone();
+            }
+            let occupied = &self.occupied[(t + 1) % self.period];
+            frontier = (0..self.height)
+                .map(|row| {
+                    let (from_west, from_east) = (east(&frontier[row]), west(&frontier[row]));
+                    let above = if row > 0 { &frontier[row - 1] } else { &none };
+                    let below = if row + 1 < self.height { &frontier[row + 1] } else { &none };
+                    let mut next: Vec<u64> = (0..mask.len())
+                        .map(|w| (frontier[row][w] | from_west[w] | from_east[w] | above[w] | below[w])
+                            & mask[w] & !occupied[row][w])
+                        .collect();
+                    if row == from.0 && !bit(&occupied[row], from.1) {
+                        set_bit(&mut next, from.1);
+                    }
+                    next
+                })
+                .collect();
+            t += 1;
+        }
+        Some(t + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (row, col) of the occupied cells at minute t
+    fn occupied(valley: &Valley, t: usize) -> Vec<(usize, usize)> {
+        let rows = &valley.occupied[t % valley.period];
+        (0..valley.height)
+            .flat_map(|row| (0..valley.width).map(move |col| (row, col)))
+            .filter(|(row, col)| bit(&rows[*row], *col))
+            .collect()
+    }
+
+    #[test]
+    fn test_occupied() {
+        // the simple example of the puzzle, plus a blizzard going left and one going up
+        let valley = Valley::new(5, 5, &[
+            Blizzard::new(GridPos::of(1, 2), '>'),
+            Blizzard::new(GridPos::of(4, 4), 'v'),
+            Blizzard::new(GridPos::of(2, 1), '<'),
+            Blizzard::new(GridPos::of(5, 1), '^'),
+        ]);
+        assert_eq!(valley.period, 5);
+        assert_eq!(occupied(&valley, 0), vec![(0, 1), (0, 4), (1, 0), (3, 3)]);
+        assert_eq!(occupied(&valley, 1), vec![(0, 0), (1, 1), (4, 3), (4, 4)]);
+        assert_eq!(occupied(&valley, 2), vec![(0, 3), (0, 4), (1, 2), (3, 4)]);
+        // both the > and the v blizzards in the same cell
+        assert_eq!(occupied(&valley, 3), vec![(0, 3), (1, 3), (2, 4)]);
+        assert_eq!(occupied(&valley, 5), occupied(&valley, 0));
+    }
+
+    #[test]
+    fn test_occupied_large() {
+        // more than 64 rows and columns: the blizzard crosses into the next word, then wraps
+        let valley = Valley::new(70, 66, &[Blizzard::new(GridPos::of(64, 66), '>')]);
+        assert_eq!(occupied(&valley, 1), vec![(65, 64)]);
+        assert_eq!(occupied(&valley, 7), vec![(65, 0)]);
+    }
+
+    #[test]
+    fn test_unreachable() {
+        // a single column of two cells, always both taken by a v and a ^ blizzard
+        let valley = Valley::new(1, 2, &[Blizzard::new(GridPos::of(1, 1), 'v'), Blizzard::new(GridPos::of(1, 2), '^')]);
+        assert_eq!(valley.crossing((0, 0), (1, 0), 0), None);
+    }
+}
@@ -24,6 +24,8 @@ mod day23;
 mod day24;
 mod day25;
 mod day16opt;
+mod day16dp;
+mod day23bits;
 
 use adventofcode::register_solver;
 
@@ -46,6 +48,7 @@ pub fn register_solvers() {
     register_solver(year, "day15", || Box::new(day15::Solution::new()));
     register_solver(year, "day16", || Box::new(day16::Solution::new()));
     register_solver(year, "day16opt", || Box::new(day16opt::Solution::new()));
+    register_solver(year, "day16dp", || Box::new(day16dp::Solution::new()));
     register_solver(year, "day17", || Box::new(day17::Solution::new()));
     register_solver(year, "day18", || Box::new(day18::Solution::new()));
     register_solver(year, "day19", || Box::new(day19::Solution::new()));
@@ -53,6 +56,7 @@ pub fn register_solvers() {
     register_solver(year, "day21", || Box::new(day21::Solution::new()));
     register_solver(year, "day22", || Box::new(day22::Solution::new()));
     register_solver(year, "day23", || Box::new(day23::Solution::new()));
+    register_solver(year, "day23bits", || Box::new(day23bits::Solution::new()));
     register_solver(year, "day24", || Box::new(day24::Solution::new()));
     register_solver(year, "day25", || Box::new(day25::Solution::new()));
 }
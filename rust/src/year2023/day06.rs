@@ -2,6 +2,7 @@
 
 use std::str::FromStr;
 use log::{debug, info};
+use adventofcode::sequence::quadratic_wins;
 use adventofcode::Solver;
 
 pub struct Solution {
@@ -40,8 +41,8 @@ impl Solver for Solution {
 
         let mut part1 = 0;
         for i in 0..races {
-            let t = self.times[i] as f64;
-            let d = self.distances[i] as f64;
+            let t = self.times[i] as i64;
+            let d = self.distances[i] as i64;
 
             // some Dream Theater please?
             let record_beaten = distance_over_time(d, t);
@@ -66,37 +67,23 @@ impl Solver for Solution {
     }
 }
 
-fn collapse(nums: &Vec<u32>) -> f64 {
+fn collapse(nums: &Vec<u32>) -> i64 {
     let mut text = String::new();
     nums.iter().for_each(|num| text.push_str(&num.to_string()));
-    f64::from_str(&text).unwrap()
+    i64::from_str(&text).unwrap()
 }
 
-fn distance_over_time(d: f64, t: f64) -> u32 {
+fn distance_over_time(d: i64, t: i64) -> i64 {
     // second degree (dis)equation: given t and d, we'll move by x * (t - x); and we need
-    // to find where this is >= d (x is the time we press the button)
-    // so equation is -x^2 + tx - d >= 0
-    // find zeroes (they need to be both > 0) and find range inside (round up and down to
-    // nearest integers)
-    let delta = t.powf(2_f64) - 4_f64 * d;
-    if delta < 0_f64 {
-        info!("No zeroes for T = {t} and D = {d}");
+    // to find where this is > d (x is the time we press the button) => integer range between the
+    // zeroes of -x^2 + tx - d, excluding them
+    let Some(range) = quadratic_wins(t, d) else {
+        info!("No winning times for T = {t} and D = {d}");
         return 0;
-    }
-    let mut min = ((t - delta.sqrt()) / 2.0).ceil();
-    let mut max = ((t + delta.sqrt()) / 2.0).floor();
-    if min == max {
-        info!("Only one zero for T = {t} and D = {d}");
-        return 0;
-    }
-    if min * (t - min) == d {
-        min += 1_f64;
-    }
-    if max * (t - max) == d {
-        max -= 1_f64;
-    }
-    let record_beaten = max as u32 - min as u32 + 1;
-    debug!("time {t}, distance {d}: -x^2 + {t}x - {d} = ({min}, {max}) => {record_beaten} possible records");
+    };
+    let record_beaten = range.end() - range.start() + 1;
+    debug!("time {t}, distance {d}: -x^2 + {t}x - {d} = ({}, {}) => {record_beaten} possible records",
+        range.start(), range.end());
 
     record_beaten
 }
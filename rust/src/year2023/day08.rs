@@ -4,6 +4,7 @@ use std::collections::HashMap;
 
 use log::{debug, info};
 
+use adventofcode::math::lcm_all;
 use adventofcode::Solver;
 
 pub struct Solution {
@@ -74,7 +75,7 @@ impl Solver for Solution {
             .collect();
         debug!("Part 2 - intial ghosts: {}", ghosts.len());
         let mut steps2 = 0;
-        let mut cycles = 1_usize;
+        let mut cycle_lengths = Vec::new();
         while !all_finished(&ghosts) {
             let new_ghosts: Vec<&str> = ghosts.iter()
                 .map(|pos| self.next_node(pos, steps2))
@@ -83,12 +84,13 @@ impl Solver for Solution {
             let (cycling, wandering): (Vec<_>, Vec<_>) = new_ghosts.into_iter()
                 .partition(|pos| pos.ends_with("Z"));
             if cycling.len() > 0 {
-                cycles = num::integer::lcm(cycles, steps2);
-                debug!("Found {} ghosts cycling at step {steps2} - {} still wandering (=> lcm {cycles})",
+                cycle_lengths.extend(cycling.iter().map(|_| steps2 as i64));
+                debug!("Found {} ghosts cycling at step {steps2} - {} still wandering",
                     cycling.len(), wandering.len());
             }
             ghosts = wandering;
         }
+        let cycles = lcm_all(cycle_lengths);
         info!("[2] Reached the end in {} steps", cycles);
 
         Some((steps1.to_string(), cycles.to_string()))
@@ -2,11 +2,12 @@
 
 use std::str::FromStr;
 use log::{debug, info};
+use adventofcode::sequence::{extrapolate_backward, extrapolate_forward};
 use adventofcode::Solver;
 
 pub struct Solution {
-    part1: i32,
-    part2: i32,
+    part1: i64,
+    part2: i64,
 }
 
 impl Solution {
@@ -20,10 +21,10 @@ impl Solution {
 
 impl Solver for Solution {
     fn parse(&mut self, line: &str) {
-        let vals = line.split(" ")
-            .map(|x| i32::from_str(x).unwrap())
+        let vals: Vec<i64> = line.split(" ")
+            .map(|x| i64::from_str(x).unwrap())
             .collect();
-        let (first, last) = extend_sequence(&vals);
+        let (first, last) = (extrapolate_backward(&vals), extrapolate_forward(&vals));
         debug!("Found side numbers for {:?} => ({} .. {})", vals, first, last);
         self.part1 += last;
         self.part2 += first;
@@ -36,30 +37,3 @@ impl Solver for Solution {
         Some((self.part1.to_string(), self.part2.to_string()))
     }
 }
-
-fn extend_sequence(initial: &Vec<i32>) -> (i32, i32) {
-    let mut deltas = Vec::new();
-    let mut diffs = sequence_diffs(initial);
-    while !diffs.iter().all(|val| *val == 0) {
-        deltas.push(diffs);
-        diffs = sequence_diffs(deltas.last().unwrap());
-    }
-    // readd
-    let mut first = 0;
-    let mut last = 0;
-    while !deltas.is_empty() {
-        let seq = deltas.pop().unwrap();
-        first = seq.first().unwrap() - first;
-        last  = seq.last().unwrap() + last;
-    }
-
-    (initial.first().unwrap() - first, initial.last().unwrap() + last)
-}
-
-fn sequence_diffs(data: &Vec<i32>) -> Vec<i32> {
-    let mut diffs = Vec::new();
-    for i in 0..data.len() - 1 {
-        diffs.push(data[i + 1] - data[i]);
-    }
-    diffs
-}
@@ -1,40 +1,112 @@
 // https://adventofcode.com/2023/day/12
 
 use std::str::FromStr;
-use log::info;
+use log::{debug, info};
+use adventofcode::memo::{CacheStats, FastHash, Memo};
 use adventofcode::Solver;
 
+const UNFOLD: usize = 5;
+const OPERATIONAL: u8 = b'.';
+const DAMAGED: u8 = b'#';
+const UNKNOWN: u8 = b'?';
+
 pub struct Solution {
-    part1: u32,
+    records: Vec<(Vec<u8>, Vec<usize>)>,
+    stats: CacheStats,
 }
 
 impl Solution {
     pub(crate) fn new() -> Solution {
         Solution {
-            part1: 0,
+            records: Vec::new(),
+            stats: CacheStats::default(),
         }
     }
+
+    fn count(&mut self, springs: &[u8], groups: &[usize]) -> u64 {
+        let mut counter = Arrangements { springs, groups, cache: Memo::fast() };
+        let total = counter.count(0, 0, 0);
+        self.stats += counter.cache.stats();
+        total
+    }
 }
 
 impl Solver for Solution {
     fn parse(&mut self, line: &str) {
         let (springs, counts) = line.split_once(" ").unwrap();
-
-        self.part1 += find_arrangements(
-            springs.chars().collect(),
-            counts.split(",").map(|num| u32::from_str(num).unwrap()).collect()
-        );
+        self.records.push((
+            springs.bytes().collect(),
+            counts.split(",").map(|num| usize::from_str(num).unwrap()).collect()
+        ));
     }
 
     fn solve(&mut self) -> Option<(String, String)> {
-        info!("[1] Found {} total arrangements", self.part1);
+        let records = std::mem::take(&mut self.records);
+        let mut part1 = 0;
+        let mut part2 = 0;
+        for (springs, groups) in &records {
+            let arrangements = self.count(springs, groups);
+            debug!("{} {:?} => {arrangements} arrangements", String::from_utf8_lossy(springs), groups);
+            part1 += arrangements;
 
-        Some((self.part1.to_string(), "".to_string()))
+            // unfold: springs are joined by an extra unknown, groups just repeated
+            let mut unfolded_springs = springs.clone();
+            for _ in 1..UNFOLD {
+                unfolded_springs.push(UNKNOWN);
+                unfolded_springs.extend_from_slice(springs);
+            }
+            part2 += self.count(&unfolded_springs, &groups.repeat(UNFOLD));
+        }
+        self.stats.report("Arrangements");
+        info!("[1] Found {} total arrangements", part1);
+        info!("[2] Found {} total arrangements after unfolding", part2);
+
+        Some((part1.to_string(), part2.to_string()))
     }
 }
 
-fn find_arrangements(springs: Vec<char>, counts: Vec<u32>) -> u32 {
-    info!("Looking to find arrangements of {:?} for counts {:?}", springs, counts);
+/// counts the arrangements of one record, memoised on (position, group index, run length) where
+/// run is the number of damaged springs already placed for the current group.
+struct Arrangements<'a> {
+    springs: &'a [u8],
+    groups: &'a [usize],
+    cache: Memo<(usize, usize, usize), u64, FastHash>,
+}
+
+impl Arrangements<'_> {
+    fn count(&mut self, pos: usize, group: usize, run: usize) -> u64 {
+        if pos == self.springs.len() {
+            // valid only if all groups are matched, possibly closing the one still open
+            let done = if run == 0 {
+                group == self.groups.len()
+            } else {
+                group == self.groups.len() - 1 && run == self.groups[group]
+            };
+            return if done { 1 } else { 0 };
+        }
+        let key = (pos, group, run);
+        if let Some(cached) = self.cache.get(&key) {
+            return *cached;
+        }
 
-    0
+        let spring = self.springs[pos];
+        let mut total = 0;
+        if spring == DAMAGED || spring == UNKNOWN {
+            // extend the current group, if there's still room
+            if group < self.groups.len() && run < self.groups[group] {
+                total += self.count(pos + 1, group, run + 1);
+            }
+        }
+        if spring == OPERATIONAL || spring == UNKNOWN {
+            // outside a group, or closing one of exactly the right length
+            if run == 0 {
+                total += self.count(pos + 1, group, 0);
+            } else if run == self.groups[group] {
+                total += self.count(pos + 1, group + 1, 0);
+            }
+        }
+
+        self.cache.insert(key, total);
+        total
+    }
 }
@@ -0,0 +1,53 @@
+// https://adventofcode.com/2024/day/1
+// ported from go: sorting both lists pairs the smallest with the smallest, and so on; for the
+// similarity a count of the right list values is simpler than walking the two sorted lists.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use log::info;
+use adventofcode::Solver;
+
+pub struct Solution {
+    left: Vec<i64>,
+    right: Vec<i64>,
+}
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            left: Vec::new(),
+            right: Vec::new(),
+        }
+    }
+}
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        let mut parts = line.split_whitespace().map(|v| i64::from_str(v).unwrap());
+        self.left.push(parts.next().unwrap());
+        self.right.push(parts.next().unwrap());
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        info!("location sizes {}/{}", self.left.len(), self.right.len());
+        assert_eq!(self.left.len(), self.right.len(), "location sizes are uneven??");
+        self.left.sort();
+        self.right.sort();
+        let part1: i64 = self.left.iter().zip(&self.right)
+            .map(|(l, r)| (l - r).abs())
+            .sum();
+        info!("[1] Total distance: {part1}");
+
+        let mut counts = HashMap::new();
+        self.right.iter().for_each(|r| *counts.entry(*r).or_insert(0) += 1);
+        let part2: i64 = self.left.iter()
+            .map(|l| l * counts.get(l).unwrap_or(&0))
+            .sum();
+        info!("[2] Similarity score: {part2}");
+
+        Some((part1.to_string(), part2.to_string()))
+    }
+}
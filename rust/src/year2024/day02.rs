@@ -0,0 +1,60 @@
+// https://adventofcode.com/2024/day/2
+// ported from go: instead of tracking the direction while walking, all the deltas must be in 1..=3,
+// or all in -3..=-1. For part 2 just try removing each level in turn, reports are short.
+
+use std::str::FromStr;
+use log::{debug, info};
+use adventofcode::Solver;
+
+pub struct Solution {
+    part1: u32,
+    part2: u32,
+}
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            part1: 0,
+            part2: 0,
+        }
+    }
+}
+
+const MAX_DELTA: i64 = 3;
+
+fn is_safe(levels: &[i64]) -> bool {
+    let deltas: Vec<i64> = levels.windows(2).map(|w| w[1] - w[0]).collect();
+    deltas.iter().all(|d| (1..=MAX_DELTA).contains(d)) || deltas.iter().all(|d| (-MAX_DELTA..=-1).contains(d))
+}
+
+/// safe if removing (at most) one level makes it safe
+fn is_tolerable(levels: &[i64]) -> bool {
+    (0..levels.len()).any(|skip| {
+        let mut fewer = levels.to_vec();
+        fewer.remove(skip);
+        is_safe(&fewer)
+    })
+}
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        let levels: Vec<i64> = line.split_whitespace().map(|v| i64::from_str(v).unwrap()).collect();
+        if is_safe(&levels) {
+            debug!("(safe) {line}");
+            self.part1 += 1;
+            self.part2 += 1;
+        } else if is_tolerable(&levels) {
+            debug!("(safe removing one level) {line}");
+            self.part2 += 1;
+        }
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        info!("[1] Safe reports: {}", self.part1);
+        info!("[2] Safe reports with one bad level: {}", self.part2);
+        Some((self.part1.to_string(), self.part2.to_string()))
+    }
+}
@@ -0,0 +1,52 @@
+// https://adventofcode.com/2024/day/3
+// ported from go: a single regex with alternatives finds mul(X,Y), do() and don't() in order;
+// the enabled status carries over between lines.
+
+use std::str::FromStr;
+use log::{debug, info};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use adventofcode::Solver;
+
+pub struct Solution {
+    enabled: bool,
+    part1: u32,
+    part2: u32,
+}
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            enabled: true,
+            part1: 0,
+            part2: 0,
+        }
+    }
+}
+
+static RE_INSTRUCTION: Lazy<Regex> = Lazy::new(|| Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)|do\(\)|don't\(\)").unwrap());
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        for captures in RE_INSTRUCTION.captures_iter(line) {
+            match &captures[0] {
+                "do()" => self.enabled = true,
+                "don't()" => self.enabled = false,
+                _ => {
+                    let val = u32::from_str(&captures[1]).unwrap() * u32::from_str(&captures[2]).unwrap();
+                    self.part1 += val;
+                    if self.enabled {
+                        self.part2 += val;
+                    }
+                    debug!("Found {} -> {val} (enabled? {})", &captures[0], self.enabled);
+                }
+            }
+        }
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        info!("[1] Sum of multiplications: {}", self.part1);
+        info!("[2] Sum of enabled multiplications: {}", self.part2);
+        Some((self.part1.to_string(), self.part2.to_string()))
+    }
+}
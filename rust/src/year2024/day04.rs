@@ -0,0 +1,80 @@
+// https://adventofcode.com/2024/day/4
+// ported from go: look for XMAS in all 8 directions starting from every X; an X-MAS is an A with
+// both diagonals reading MAS or SAM.
+
+use log::info;
+use adventofcode::grid::{GridPos, ALL_SURROUNDING, MOVE_DL, MOVE_DR, MOVE_UL, MOVE_UR};
+use adventofcode::Solver;
+
+pub struct Solution {
+    puzzle: Vec<Vec<u8>>,
+    width: usize,
+}
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            puzzle: Vec::new(),
+            width: 0,
+        }
+    }
+
+    fn letter(&self, pos: &GridPos) -> Option<u8> {
+        pos.to_linear(self.width, self.puzzle.len())
+            .map(|_| self.puzzle[pos.row as usize][pos.col as usize])
+    }
+
+    fn is_word(&self, start: &GridPos, dir: &GridPos, word: &str) -> bool {
+        let mut pos = start.clone();
+        for ch in word.bytes() {
+            if self.letter(&pos) != Some(ch) {
+                return false;
+            }
+            pos.move_by(dir);
+        }
+        true
+    }
+
+    fn is_x_mas(&self, center: &GridPos) -> bool {
+        let diagonal = |from: &GridPos, dir: &GridPos| {
+            let start = center.add(from);
+            self.is_word(&start, dir, CROSS) || self.is_word(&start, dir, CROSS_BACK)
+        };
+        diagonal(&MOVE_UL, &MOVE_DR) && diagonal(&MOVE_UR, &MOVE_DL)
+    }
+}
+
+const WORD: &str = "XMAS";
+const CROSS: &str = "MAS";
+const CROSS_BACK: &str = "SAM";
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        if self.width == 0 {
+            self.width = line.len();
+        }
+        assert_eq!(self.width, line.len(), "Unexpected line length");
+        self.puzzle.push(line.bytes().collect());
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        let mut part1 = 0;
+        let mut part2 = 0;
+        for row in 0..self.puzzle.len() {
+            for col in 0..self.width {
+                let pos = GridPos::of(col as i64, row as i64);
+                match self.puzzle[row][col] {
+                    b'X' => part1 += ALL_SURROUNDING.iter().filter(|dir| self.is_word(&pos, dir, WORD)).count(),
+                    b'A' if self.is_x_mas(&pos) => part2 += 1,
+                    _ => {}
+                }
+            }
+        }
+        info!("[1] Found {part1} XMAS");
+        info!("[2] Found {part2} X-MAS");
+        Some((part1.to_string(), part2.to_string()))
+    }
+}
@@ -0,0 +1,90 @@
+// https://adventofcode.com/2024/day/5
+// ported from go, but instead of swapping pages until no rule is violated, the wrong updates are
+// re-sorted with a topological sort of the rules restricted to their pages.
+
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use log::{debug, info};
+use adventofcode::Solver;
+
+pub struct Solution {
+    /// page => pages that must come after it
+    rules: HashMap<u32, HashSet<u32>>,
+    updates: Vec<Vec<u32>>,
+}
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            rules: HashMap::new(),
+            updates: Vec::new(),
+        }
+    }
+
+    fn must_precede(&self, before: u32, after: u32) -> bool {
+        self.rules.get(&before).is_some_and(|pages| pages.contains(&after))
+    }
+
+    fn is_ordered(&self, update: &[u32]) -> bool {
+        update.iter().enumerate()
+            .all(|(i, page)| update[..i].iter().all(|prev| !self.must_precede(*page, *prev)))
+    }
+
+    /// Kahn's algorithm over the rules involving only the pages in the update
+    fn reorder(&self, update: &[u32]) -> Vec<u32> {
+        let mut incoming: HashMap<u32, usize> = update.iter()
+            .map(|page| (*page, update.iter().filter(|other| self.must_precede(**other, *page)).count()))
+            .collect();
+        let mut ready: Vec<u32> = update.iter().filter(|page| incoming[page] == 0).copied().collect();
+        let mut sorted = Vec::with_capacity(update.len());
+        while let Some(page) = ready.pop() {
+            sorted.push(page);
+            for next in update.iter().filter(|next| self.must_precede(page, **next)) {
+                let count = incoming.get_mut(next).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    ready.push(*next);
+                }
+            }
+        }
+        assert_eq!(sorted.len(), update.len(), "Rules have a cycle for {:?}", update);
+        sorted
+    }
+}
+
+fn middle(pages: &[u32]) -> u32 {
+    assert_eq!(pages.len() % 2, 1, "Update with even pages? {:?}", pages);
+    pages[pages.len() / 2]
+}
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        if let Some((before, after)) = line.split_once('|') {
+            self.rules.entry(u32::from_str(before).unwrap())
+                .or_default()
+                .insert(u32::from_str(after).unwrap());
+        } else {
+            self.updates.push(line.split(',').map(|v| u32::from_str(v).unwrap()).collect());
+        }
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        let mut part1 = 0;
+        let mut part2 = 0;
+        for update in &self.updates {
+            if self.is_ordered(update) {
+                part1 += middle(update);
+            } else {
+                let sorted = self.reorder(update);
+                debug!("{:?} reordered to {:?}", update, sorted);
+                part2 += middle(&sorted);
+            }
+        }
+        info!("[1] Sum of middle pages of correct updates: {part1}");
+        info!("[2] Sum of middle pages of reordered updates: {part2}");
+        Some((part1.to_string(), part2.to_string()))
+    }
+}
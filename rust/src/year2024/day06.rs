@@ -0,0 +1,96 @@
+// https://adventofcode.com/2024/day/6
+// ported from go: the guard loops when it's again in the same position facing the same direction,
+// so each cell keeps a bitmask of the directions it was walked in. An obstacle can only change the
+// walk if the guard bumps into it, so part 2 only tries the cells of the original path; each try is
+// independent from the others, so they're split among threads.
+
+use std::thread;
+use log::{debug, info};
+use adventofcode::grid::{GridPos, MOVE_D, MOVE_L, MOVE_R, MOVE_U};
+use adventofcode::Solver;
+
+pub struct Solution {
+    obstacles: Vec<bool>,
+    width: usize,
+    height: usize,
+    start: GridPos,
+}
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            obstacles: Vec::new(),
+            width: 0,
+            height: 0,
+            start: GridPos::of(0, 0),
+        }
+    }
+
+    /// walks the guard from the start, with an extra obstacle if given: returns the cells walked
+    /// with the directions the guard had on each (as bitmask), or None if the guard loops.
+    fn walk(&self, extra: Option<usize>) -> Option<Vec<u8>> {
+        let mut walked = vec![0u8; self.obstacles.len()];
+        let mut pos = self.start.clone();
+        let mut dir = 0;
+        let mut idx = pos.to_linear(self.width, self.height).unwrap();
+        loop {
+            if walked[idx] & (1 << dir) != 0 {
+                return None;
+            }
+            walked[idx] |= 1 << dir;
+            let next = pos.add(&DIRS[dir]);
+            match next.to_linear(self.width, self.height) {
+                None => return Some(walked),
+                Some(next_idx) if self.obstacles[next_idx] || extra == Some(next_idx) => dir = (dir + 1) % DIRS.len(),
+                Some(next_idx) => {
+                    pos = next;
+                    idx = next_idx;
+                }
+            }
+        }
+    }
+}
+
+/// turning right, starting upwards (rows grow downwards)
+const DIRS: [GridPos; 4] = [MOVE_D, MOVE_R, MOVE_U, MOVE_L];
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        if self.width == 0 {
+            self.width = line.len();
+        }
+        assert_eq!(self.width, line.len(), "Map is not a rectangle?");
+        if let Some(col) = line.find('^') {
+            self.start = GridPos::of(col as i64, self.height as i64);
+        }
+        self.obstacles.extend(line.chars().map(|ch| ch == '#'));
+        self.height += 1;
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        info!("Guard starting at position {} - map is ({}x{})", self.start, self.width, self.height);
+        let walked = self.walk(None).expect("Guard loops without obstacles?");
+        let start = self.start.to_linear(self.width, self.height).unwrap();
+        let path: Vec<usize> = (0..walked.len()).filter(|idx| walked[*idx] != 0).collect();
+        let part1 = path.len();
+        info!("[1] Guard walks on {part1} positions");
+
+        let candidates: Vec<usize> = path.into_iter().filter(|idx| *idx != start).collect();
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk_size = candidates.len().div_ceil(threads).max(1);
+        let solution = &*self;
+        let part2: usize = thread::scope(|scope| {
+            let handles: Vec<_> = candidates.chunks(chunk_size)
+                .map(|chunk| scope.spawn(move || chunk.iter().filter(|idx| solution.walk(Some(**idx)).is_none()).count()))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).sum()
+        });
+        debug!("Tried {} obstacles on {threads} threads", candidates.len());
+        info!("[2] Guard loops with {part2} different obstacles");
+
+        Some((part1.to_string(), part2.to_string()))
+    }
+}
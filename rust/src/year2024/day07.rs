@@ -0,0 +1,73 @@
+// https://adventofcode.com/2024/day/7
+// operators are evaluated left to right, so the last one can be undone from the test value: it's a
+// `+` only if the last number is not bigger, a `*` only if it divides it, a `||` only if the value
+// ends with its digits. Working backwards prunes almost every branch right away.
+
+use std::str::FromStr;
+use log::{debug, info};
+use adventofcode::Solver;
+
+pub struct Solution {
+    part1: u64,
+    part2: u64,
+}
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            part1: 0,
+            part2: 0,
+        }
+    }
+}
+
+/// true if some combination of operators applied to the numbers gives the target
+fn solvable(target: u64, numbers: &[u64], concat: bool) -> bool {
+    let (last, rest) = numbers.split_last().unwrap();
+    if rest.is_empty() {
+        return target == *last;
+    }
+    if target >= *last && solvable(target - last, rest, concat) {
+        return true;
+    }
+    // anything times 0 is 0 (and there's nothing to divide by)
+    if *last == 0 && target == 0 {
+        return true;
+    }
+    if *last != 0 && target.is_multiple_of(*last) && solvable(target / last, rest, concat) {
+        return true;
+    }
+    if concat {
+        let shift = 10u64.pow(last.checked_ilog10().unwrap_or(0) + 1);
+        if target % shift == *last && solvable(target / shift, rest, concat) {
+            return true;
+        }
+    }
+    false
+}
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        let (test, numbers) = line.split_once(": ").expect("Error parsing input");
+        let test = u64::from_str(test).unwrap();
+        let numbers: Vec<u64> = numbers.split(' ').map(|v| u64::from_str(v).unwrap()).collect();
+        if solvable(test, &numbers, false) {
+            debug!("[1] {:?} => {test}", numbers);
+            // what works for part 1 works for part 2
+            self.part1 += test;
+            self.part2 += test;
+        } else if solvable(test, &numbers, true) {
+            debug!("[2] {:?} => {test}", numbers);
+            self.part2 += test;
+        }
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        info!("[1] Total calibration: {}", self.part1);
+        info!("[2] Total calibration with concatenation: {}", self.part2);
+        Some((self.part1.to_string(), self.part2.to_string()))
+    }
+}
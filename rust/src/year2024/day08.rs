@@ -0,0 +1,79 @@
+// https://adventofcode.com/2024/day/8
+// for each pair of antennas with the same frequency, the antinodes are at multiples of their
+// distance: just the first one outside each antenna for part 1, all of them in the map for part 2.
+
+use std::collections::{HashMap, HashSet};
+use log::{debug, info};
+use adventofcode::grid::GridPos;
+use adventofcode::Solver;
+
+pub struct Solution {
+    antennas: HashMap<char, Vec<GridPos>>,
+    width: usize,
+    height: usize,
+}
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            antennas: HashMap::new(),
+            width: 0,
+            height: 0,
+        }
+    }
+
+    fn in_map(&self, pos: &GridPos) -> bool {
+        pos.to_linear(self.width, self.height).is_some()
+    }
+
+    /// antinodes from `from` going away by `step`, in the map: only the first if not resonant
+    fn antinodes(&self, from: &GridPos, step: &GridPos, resonant: bool, found: &mut HashSet<GridPos>) {
+        let mut pos = if resonant { from.clone() } else { from.add(step) };
+        while self.in_map(&pos) {
+            found.insert(pos.clone());
+            if !resonant {
+                break;
+            }
+            pos.move_by(step);
+        }
+    }
+
+    fn count_antinodes(&self, resonant: bool) -> usize {
+        let mut found = HashSet::new();
+        for (frequency, antennas) in &self.antennas {
+            debug!("Found {} antennas at frequency '{frequency}'", antennas.len());
+            for (i, a) in antennas.iter().enumerate() {
+                for b in &antennas[i + 1..] {
+                    let (dx, dy) = b.distance(a);
+                    self.antinodes(b, &GridPos::of(dx, dy), resonant, &mut found);
+                    self.antinodes(a, &GridPos::of(-dx, -dy), resonant, &mut found);
+                }
+            }
+        }
+        found.len()
+    }
+}
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        if self.width == 0 {
+            self.width = line.len();
+        }
+        assert_eq!(self.width, line.len(), "Wrong line length at line {}?", self.height);
+        line.char_indices()
+            .filter(|(_, ch)| *ch != '.')
+            .for_each(|(col, ch)| self.antennas.entry(ch).or_default().push(GridPos::of(col as i64, self.height as i64)));
+        self.height += 1;
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        let part1 = self.count_antinodes(false);
+        info!("[1] Antinodes: {part1}");
+        let part2 = self.count_antinodes(true);
+        info!("[2] Antinodes with resonant harmonics: {part2}");
+        Some((part1.to_string(), part2.to_string()))
+    }
+}
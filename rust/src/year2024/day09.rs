@@ -0,0 +1,139 @@
+// https://adventofcode.com/2024/day/9
+// the disk is never expanded block by block: files and free space are spans (start, length), and a
+// file span checksum is id * (start + ... + start + length - 1), a plain arithmetic series.
+// Part 1 moves blocks from the rightmost files into the leftmost free spans with two pointers.
+// Part 2 keeps the free spans in one min-heap (by start) per size: the leftmost span that fits a
+// file is the smallest top among the heaps of sizes >= the file length, and what's left of it after
+// the move goes back into the heap of the smaller size.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use log::{debug, info};
+use adventofcode::Solver;
+
+pub struct Solution {
+    /// file spans, the index is the file id
+    files: Vec<Span>,
+    /// free spans, in disk order
+    free: Vec<Span>,
+}
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            files: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// moves single blocks from the end of the disk to the first free blocks
+    fn compact_blocks(&self) -> u64 {
+        let mut remaining: Vec<usize> = self.files.iter().map(|f| f.len).collect();
+        let mut checksum = 0;
+        let mut last = self.files.len() - 1;
+        for (id, file) in self.files.iter().enumerate() {
+            // whatever wasn't moved away of this file stays in place
+            checksum += Span { start: file.start, len: remaining[id] }.checksum(id);
+            remaining[id] = 0;
+            let Some(free) = self.free.get(id) else { break };
+            let mut pos = free.start;
+            let end = free.start + free.len;
+            while pos < end && last > id {
+                let moved = remaining[last].min(end - pos);
+                checksum += Span { start: pos, len: moved }.checksum(last);
+                remaining[last] -= moved;
+                pos += moved;
+                if remaining[last] == 0 {
+                    last -= 1;
+                }
+            }
+        }
+        checksum
+    }
+
+    /// moves whole files, from the highest id, to the leftmost free span that can fit them
+    fn compact_files(&self) -> u64 {
+        let mut by_size: Vec<BinaryHeap<Reverse<usize>>> = vec![BinaryHeap::new(); MAX_SPAN + 1];
+        self.free.iter()
+            .filter(|span| span.len > 0)
+            .for_each(|span| by_size[span.len].push(Reverse(span.start)));
+
+        let mut checksum = 0;
+        let mut moved = 0;
+        for (id, file) in self.files.iter().enumerate().rev() {
+            let leftmost = (file.len..=MAX_SPAN)
+                .filter_map(|size| by_size[size].peek().map(|Reverse(start)| (*start, size)))
+                .filter(|(start, _)| *start < file.start)
+                .min();
+            let start = match leftmost {
+                Some((start, size)) => {
+                    by_size[size].pop();
+                    if size > file.len {
+                        by_size[size - file.len].push(Reverse(start + file.len));
+                    }
+                    moved += 1;
+                    start
+                }
+                None => file.start,
+            };
+            checksum += Span { start, len: file.len }.checksum(id);
+        }
+        debug!("Moved {moved}/{} files", self.files.len());
+        checksum
+    }
+}
+
+/// digits in the disk map
+const MAX_SPAN: usize = 9;
+
+#[derive(Clone, Debug)]
+struct Span {
+    start: usize,
+    len: usize,
+}
+
+impl Span {
+    fn checksum(&self, id: usize) -> u64 {
+        let (start, len) = (self.start as u64, self.len as u64);
+        id as u64 * (len * start + len * len.saturating_sub(1) / 2)
+    }
+}
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        let mut start = 0;
+        for (i, ch) in line.chars().enumerate() {
+            let len = ch.to_digit(10).unwrap_or_else(|| panic!("Invalid disk map digit: {ch}")) as usize;
+            if i % 2 == 0 {
+                self.files.push(Span { start, len });
+            } else {
+                self.free.push(Span { start, len });
+            }
+            start += len;
+        }
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        debug!("Disk has {} files and {} free spans", self.files.len(), self.free.len());
+        let part1 = self.compact_blocks();
+        info!("[1] Checksum after moving blocks: {part1}");
+        let part2 = self.compact_files();
+        info!("[2] Checksum after moving files: {part2}");
+        Some((part1.to_string(), part2.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_checksum() {
+        // 0099811188827773336446555566.. => blocks 2..4 are file 9
+        assert_eq!(Span { start: 2, len: 2 }.checksum(9), 9 * (2 + 3));
+        assert_eq!(Span { start: 5, len: 0 }.checksum(3), 0);
+    }
+}
@@ -0,0 +1,110 @@
+// https://adventofcode.com/2024/day/10
+// one DFS answers both parts: from each cell, the summits it can reach (score) and the number of
+// distinct trails to them (rating) only depend on the cell, so they're memoised per cell and each
+// trailhead just combines its neighbours one step higher.
+
+use std::collections::HashSet;
+use log::info;
+use adventofcode::grid::{GridPos, ALL_ORTHOGONAL};
+use adventofcode::memo::{FastHash, Memo};
+use adventofcode::Solver;
+
+pub struct Solution {
+    heights: Vec<u8>,
+    width: usize,
+    height: usize,
+}
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            heights: Vec::new(),
+            width: 0,
+            height: 0,
+        }
+    }
+
+    /// summits reachable from the cell, and number of distinct trails to them
+    fn trails(&self, idx: usize, memo: &mut Memo<usize, (HashSet<usize>, u64), FastHash>) -> (HashSet<usize>, u64) {
+        if self.heights[idx] == SUMMIT {
+            return (HashSet::from([idx]), 1);
+        }
+        if let Some(found) = memo.get(&idx) {
+            return found.clone();
+        }
+        let pos = GridPos::from_linear(idx, self.width);
+        let mut summits = HashSet::new();
+        let mut rating = 0;
+        for dir in &ALL_ORTHOGONAL {
+            let Some(next) = pos.add(dir).to_linear(self.width, self.height) else { continue };
+            if self.heights[next] == self.heights[idx] + 1 {
+                let (reached, trails) = self.trails(next, memo);
+                summits.extend(reached);
+                rating += trails;
+            }
+        }
+        memo.insert(idx, (summits.clone(), rating));
+        (summits, rating)
+    }
+}
+
+const TRAILHEAD: u8 = 0;
+const SUMMIT: u8 = 9;
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        if self.width == 0 {
+            self.width = line.len();
+        }
+        assert_eq!(self.width, line.len(), "Map is not a rectangle?");
+        // impassable tiles ('.' in some examples) can't be on any trail
+        self.heights.extend(line.chars().map(|ch| ch.to_digit(10).map_or(u8::MAX - 1, |h| h as u8)));
+        self.height += 1;
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        let mut memo = Memo::fast();
+        let mut part1 = 0;
+        let mut part2 = 0;
+        for idx in (0..self.heights.len()).filter(|idx| self.heights[*idx] == TRAILHEAD) {
+            let (summits, rating) = self.trails(idx, &mut memo);
+            part1 += summits.len();
+            part2 += rating;
+        }
+        memo.report("Trails");
+        info!("[1] Sum of trailhead scores: {part1}");
+        info!("[2] Sum of trailhead ratings: {part2}");
+        Some((part1.to_string(), part2.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(lines: &[&str]) -> Solution {
+        let mut solution = Solution::new();
+        lines.iter().for_each(|l| solution.parse(l));
+        solution
+    }
+
+    #[test]
+    fn test_score() {
+        // a single trailhead reaching the two summits at the bottom corners
+        let map = map(&["...0...", "...1...", "...2...", "6543456", "7.....7", "8.....8", "9.....9"]);
+        let (summits, _) = map.trails(3, &mut Memo::fast());
+        assert_eq!(summits, HashSet::from([6 * 7, 6 * 7 + 6]));
+    }
+
+    #[test]
+    fn test_rating() {
+        // three distinct trails to the same summit
+        let map = map(&[".....0.", "..4321.", "..5..2.", "..6543.", "..7..4.", "..8765.", "..9...."]);
+        let (summits, rating) = map.trails(5, &mut Memo::fast());
+        assert_eq!(summits.len(), 1);
+        assert_eq!(rating, 3);
+    }
+}
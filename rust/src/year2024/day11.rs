@@ -0,0 +1,89 @@
+// https://adventofcode.com/2024/day/11
+// the order of the stones never matters and many stones share the same number, so each blink
+// only transforms the count of stones by value: a few thousand distinct values even after 75 blinks.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use log::{debug, info};
+use adventofcode::Solver;
+
+pub struct Solution {
+    stones: Vec<u64>,
+}
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            stones: Vec::new(),
+        }
+    }
+}
+
+const BLINKS1: usize = 25;
+const BLINKS2: usize = 75;
+
+fn change(stone: u64) -> Vec<u64> {
+    if stone == 0 {
+        return vec![1];
+    }
+    let digits = stone.ilog10() + 1;
+    if digits.is_multiple_of(2) {
+        let half = 10u64.pow(digits / 2);
+        return vec![stone / half, stone % half];
+    }
+    vec![stone * 2024]
+}
+
+fn blink(counts: &HashMap<u64, u64>) -> HashMap<u64, u64> {
+    let mut next = HashMap::with_capacity(counts.len());
+    for (stone, count) in counts {
+        for changed in change(*stone) {
+            *next.entry(changed).or_insert(0) += count;
+        }
+    }
+    next
+}
+
+/// number of stones after the given blinks
+fn count_stones(stones: &[u64], blinks: usize) -> u64 {
+    let mut counts = HashMap::new();
+    stones.iter().for_each(|s| *counts.entry(*s).or_insert(0) += 1);
+    for _ in 0..blinks {
+        counts = blink(&counts);
+    }
+    debug!("{} distinct stones after {blinks} blinks", counts.len());
+    counts.values().sum()
+}
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        self.stones.extend(line.split_whitespace().map(|v| u64::from_str(v).unwrap()));
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        let part1 = count_stones(&self.stones, BLINKS1);
+        info!("[1] Stones after {BLINKS1} blinks: {part1}");
+        let part2 = count_stones(&self.stones, BLINKS2);
+        info!("[2] Stones after {BLINKS2} blinks: {part2}");
+        Some((part1.to_string(), part2.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change() {
+        assert_eq!(change(0), vec![1]);
+        assert_eq!(change(1000), vec![10, 0]);
+        assert_eq!(change(999), vec![2021976]);
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(count_stones(&[0, 1, 10, 99, 999], 1), 7);
+        assert_eq!(count_stones(&[125, 17], 6), 22);
+        assert_eq!(count_stones(&[125, 17], 25), 55312);
+    }
+}
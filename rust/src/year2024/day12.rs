@@ -0,0 +1,74 @@
+// https://adventofcode.com/2024/day/12
+// regions are the connected components of the garden (grid::label_regions); then each plot adds
+// to its region area, perimeter (sides not touching the same region) and corners: a polygon has as
+// many sides as corners, and each plot can see one in each diagonal quadrant, either convex (both
+// orthogonal neighbours outside) or concave (both inside, but the diagonal outside).
+
+use log::{debug, info};
+use adventofcode::grid::{label_regions, GridPos, ALL_ORTHOGONAL, MOVE_D, MOVE_L, MOVE_R, MOVE_U};
+use adventofcode::Solver;
+
+pub struct Solution {
+    plots: Vec<char>,
+    width: usize,
+    height: usize,
+}
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            plots: Vec::new(),
+            width: 0,
+            height: 0,
+        }
+    }
+}
+
+/// orthogonal pairs around each corner of a plot
+const QUADRANTS: [(GridPos, GridPos); 4] = [(MOVE_U, MOVE_R), (MOVE_R, MOVE_D), (MOVE_D, MOVE_L), (MOVE_L, MOVE_U)];
+
+#[derive(Default, Clone, Debug)]
+struct Region {
+    area: usize,
+    perimeter: usize,
+    sides: usize,
+}
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        if self.width == 0 {
+            self.width = line.len();
+        }
+        assert_eq!(self.width, line.len(), "Garden is not a rectangle?");
+        self.plots.extend(line.chars());
+        self.height += 1;
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        let (labels, count) = label_regions(&self.plots, self.width);
+        debug!("Found {count} regions");
+        let mut regions = vec![Region::default(); count];
+        for (idx, label) in labels.iter().enumerate() {
+            let pos = GridPos::from_linear(idx, self.width);
+            let same = |delta: &GridPos| pos.add(delta).to_linear(self.width, self.height)
+                .is_some_and(|other| labels[other] == *label);
+            let region = &mut regions[*label];
+            region.area += 1;
+            region.perimeter += ALL_ORTHOGONAL.iter().filter(|dir| !same(dir)).count();
+            region.sides += QUADRANTS.iter()
+                .filter(|(a, b)| {
+                    let (in_a, in_b) = (same(a), same(b));
+                    (!in_a && !in_b) || (in_a && in_b && !same(&a.add(b)))
+                })
+                .count();
+        }
+        let part1: usize = regions.iter().map(|r| r.area * r.perimeter).sum();
+        info!("[1] Fence price: {part1}");
+        let part2: usize = regions.iter().map(|r| r.area * r.sides).sum();
+        info!("[2] Fence price with bulk discount: {part2}");
+        Some((part1.to_string(), part2.to_string()))
+    }
+}
@@ -0,0 +1,175 @@
+// https://adventofcode.com/2024/day/13
+// each machine is a 2x2 linear system a * A + b * B = P: with Cramer's rule and integers only, a
+// solution is valid only if both divisions are exact and non-negative. When the two buttons are
+// collinear there are either none or infinitely many solutions: it's a linear diophantine equation
+// on one axis, and the cheapest one is at one end of the range of non-negative solutions.
+
+use std::str::FromStr;
+use log::{debug, info};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use adventofcode::grid::GridPos;
+use adventofcode::math::extended_gcd;
+use adventofcode::Solver;
+
+pub struct Solution {
+    machines: Vec<Machine>,
+    buttons: Vec<GridPos>,
+}
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            machines: Vec::new(),
+            buttons: Vec::new(),
+        }
+    }
+
+    /// tokens to win all the possible prizes
+    fn tokens(&self, offset: i64) -> i64 {
+        self.machines.iter()
+            .filter_map(|m| m.presses(offset))
+            .map(|(a, b)| a * COST_A + b * COST_B)
+            .sum()
+    }
+}
+
+static RE_BUTTON: Lazy<Regex> = Lazy::new(|| Regex::new(r"Button [AB]: X\+(\d+), Y\+(\d+)").unwrap());
+static RE_PRIZE: Lazy<Regex> = Lazy::new(|| Regex::new(r"Prize: X=(\d+), Y=(\d+)").unwrap());
+const COST_A: i64 = 3;
+const COST_B: i64 = 1;
+const OFFSET: i64 = 10000000000000;
+
+struct Machine {
+    a: GridPos,
+    b: GridPos,
+    prize: GridPos,
+}
+
+fn cross(u: &GridPos, v: &GridPos) -> i64 {
+    u.col * v.row - u.row * v.col
+}
+
+impl Machine {
+    /// presses of A and B to win the prize (moved by offset on both axes), the cheapest if many
+    fn presses(&self, offset: i64) -> Option<(i64, i64)> {
+        let prize = GridPos::of(self.prize.col + offset, self.prize.row + offset);
+        let det = cross(&self.a, &self.b);
+        if det == 0 {
+            return self.collinear_presses(&prize);
+        }
+        // Cramer's rule
+        let a = cross(&prize, &self.b);
+        let b = cross(&self.a, &prize);
+        if a % det != 0 || b % det != 0 || a / det < 0 || b / det < 0 {
+            return None;
+        }
+        Some((a / det, b / det))
+    }
+
+    fn collinear_presses(&self, prize: &GridPos) -> Option<(i64, i64)> {
+        if cross(&self.a, prize) != 0 || cross(&self.b, prize) != 0 {
+            // prize is off the line of the buttons
+            return None;
+        }
+        // everything is on one line: one axis is enough (the other one unless it's all zeroes)
+        let (u, v, p) = if self.a.col != 0 || self.b.col != 0 {
+            (self.a.col, self.b.col, prize.col)
+        } else {
+            (self.a.row, self.b.row, prize.row)
+        };
+        debug!("Collinear buttons: {u} * a + {v} * b = {p}");
+        if u == 0 || v == 0 {
+            return match (u, v) {
+                (0, 0) => (p == 0).then_some((0, 0)),
+                (0, _) => (p % v == 0).then_some((0, p / v)),
+                _ => (p % u == 0).then_some((p / u, 0)),
+            };
+        }
+        let (g, x, y) = extended_gcd(u, v);
+        if p % g != 0 {
+            return None;
+        }
+        // all the solutions: a = a0 + k * v / g, b = b0 - k * u / g
+        let (a0, b0) = (x as i128 * (p / g) as i128, y as i128 * (p / g) as i128);
+        let (step_a, step_b) = ((v / g) as i128, (u / g) as i128);
+        // a >= 0 and b >= 0
+        let k_min = -(a0.div_euclid(step_a));
+        let k_min = if a0 + k_min * step_a < 0 { k_min + 1 } else { k_min };
+        let k_max = b0.div_euclid(step_b);
+        if k_min > k_max {
+            return None;
+        }
+        // cost changes by (COST_A * step_a - COST_B * step_b) for each k
+        let k = if COST_A as i128 * step_a > COST_B as i128 * step_b { k_min } else { k_max };
+        Some(((a0 + k * step_a) as i64, (b0 - k * step_b) as i64))
+    }
+}
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        let pos = |captures: regex::Captures| GridPos::of(
+            i64::from_str(&captures[1]).unwrap(), i64::from_str(&captures[2]).unwrap());
+        if let Some(captures) = RE_BUTTON.captures(line) {
+            self.buttons.push(pos(captures));
+        } else if let Some(captures) = RE_PRIZE.captures(line) {
+            assert_eq!(self.buttons.len(), 2, "Prize without two buttons: {line}");
+            let b = self.buttons.pop().unwrap();
+            let a = self.buttons.pop().unwrap();
+            self.machines.push(Machine { a, b, prize: pos(captures) });
+        } else {
+            panic!("Unknown line: {line}");
+        }
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        debug!("Found {} claw machines", self.machines.len());
+        let part1 = self.tokens(0);
+        info!("[1] Tokens to win all prizes: {part1}");
+        let part2 = self.tokens(OFFSET);
+        info!("[2] Tokens to win all prizes, after the conversion error: {part2}");
+        Some((part1.to_string(), part2.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn machine(a: (i64, i64), b: (i64, i64), prize: (i64, i64)) -> Machine {
+        Machine { a: GridPos::of(a.0, a.1), b: GridPos::of(b.0, b.1), prize: GridPos::of(prize.0, prize.1) }
+    }
+
+    #[test]
+    fn test_examples() {
+        let machines = [machine((94, 34), (22, 67), (8400, 5400)),
+            machine((26, 66), (67, 21), (12748, 12176)),
+            machine((17, 86), (84, 37), (7870, 6450)),
+            machine((69, 23), (27, 71), (18641, 10279))];
+        let presses: Vec<_> = machines.iter().map(|m| m.presses(0)).collect();
+        assert_eq!(presses, vec![Some((80, 40)), None, Some((38, 86)), None]);
+        let presses: Vec<_> = machines.iter().map(|m| m.presses(OFFSET).is_some()).collect();
+        assert_eq!(presses, vec![false, true, false, true]);
+    }
+
+    #[test]
+    fn test_negative() {
+        // 2 * A - 1 * B would do, but presses can't be negative
+        assert_eq!(machine((1, 1), (1, 2), (1, 0)).presses(0), None);
+    }
+
+    #[test]
+    fn test_collinear() {
+        // A moves 2 times B for 3 times the cost: pressing only B is cheaper
+        assert_eq!(machine((2, 2), (1, 1), (8, 8)).presses(0), Some((0, 8)));
+        // A moves 4 times B: pressing A is cheaper, B makes up the remainder
+        assert_eq!(machine((8, 4), (2, 1), (18, 9)).presses(0), Some((2, 1)));
+        // only some combinations reach the prize
+        assert_eq!(machine((4, 2), (6, 3), (14, 7)).presses(0), Some((2, 1)));
+        assert_eq!(machine((4, 2), (6, 3), (15, 7)).presses(0), None);
+        assert_eq!(machine((4, 2), (6, 3), (5, 2)).presses(0), None);
+    }
+}
@@ -0,0 +1,128 @@
+// https://adventofcode.com/2024/day/14
+// robots move in straight lines on a torus, so their x repeats every `width` seconds and their y
+// every `height` seconds. The tree frame is the one where robots bunch together: the second with
+// the minimum variance of the x coordinates (mod width) and the one with the minimum variance of
+// the y (mod height) are found separately, then combined with the CRT into the single frame.
+// The example room is too small (and has too few robots) to draw anything: no tree there.
+
+use std::str::FromStr;
+use log::{debug, info};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use adventofcode::grid::GridPos;
+use adventofcode::math::{crt, modulo};
+use adventofcode::Solver;
+
+pub struct Solution {
+    robots: Vec<Robot>,
+    width: i64,
+    height: i64,
+}
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            robots: Vec::new(),
+            width: 101,
+            height: 103,
+        }
+    }
+
+    fn positions(&self, seconds: i64) -> Vec<GridPos> {
+        self.robots.iter().map(|r| r.at(seconds, self.width, self.height)).collect()
+    }
+
+    fn safety_factor(&self, seconds: i64) -> usize {
+        let (mid_x, mid_y) = (self.width / 2, self.height / 2);
+        let mut quadrants = [0; 4];
+        for pos in self.positions(seconds) {
+            if pos.col == mid_x || pos.row == mid_y {
+                continue;
+            }
+            quadrants[(pos.col > mid_x) as usize + 2 * (pos.row > mid_y) as usize] += 1;
+        }
+        debug!("Robots in quadrants: {:?}", quadrants);
+        quadrants.iter().product()
+    }
+
+    /// second (within the period) with the minimum variance of one coordinate, if it stands out
+    fn most_bunched(&self, period: i64, coord: impl Fn(&GridPos) -> i64) -> Option<i64> {
+        // n^2 * variance, to stay on integers
+        let variances: Vec<i64> = (0..period)
+            .map(|t| {
+                let values: Vec<i64> = self.positions(t).iter().map(&coord).collect();
+                let sum: i64 = values.iter().sum();
+                let squares: i64 = values.iter().map(|v| v * v).sum();
+                values.len() as i64 * squares - sum * sum
+            })
+            .collect();
+        let mean = variances.iter().sum::<i64>() / period;
+        let (t, min) = variances.iter().enumerate().min_by_key(|(_, v)| **v).unwrap();
+        debug!("Minimum variance at {t}: {min} (mean {mean})");
+        (*min * BUNCHED_RATIO < mean).then_some(t as i64)
+    }
+
+    fn draw(&self, seconds: i64) {
+        let mut image = vec![vec!['.'; self.width as usize]; self.height as usize];
+        self.positions(seconds).iter().for_each(|pos| image[pos.row as usize][pos.col as usize] = '#');
+        image.iter().for_each(|row| debug!("{}", row.iter().collect::<String>()));
+    }
+}
+
+static RE_ROBOT: Lazy<Regex> = Lazy::new(|| Regex::new(r"p=(-?\d+),(-?\d+) v=(-?\d+),(-?\d+)").unwrap());
+const SECONDS: i64 = 100;
+/// the tree frame variance must be less than this fraction of the average
+const BUNCHED_RATIO: i64 = 2;
+
+struct Robot {
+    pos: GridPos,
+    velocity: GridPos,
+}
+
+impl Robot {
+    fn at(&self, seconds: i64, width: i64, height: i64) -> GridPos {
+        GridPos::of(modulo(self.pos.col + self.velocity.col * seconds, width),
+                    modulo(self.pos.row + self.velocity.row * seconds, height))
+    }
+}
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        if let Some(width) = line.strip_prefix("room width: ") {
+            self.width = i64::from_str(width).unwrap();
+        } else if let Some(height) = line.strip_prefix("room height: ") {
+            self.height = i64::from_str(height).unwrap();
+        } else if let Some(captures) = RE_ROBOT.captures(line) {
+            let val = |i| i64::from_str(&captures[i]).unwrap();
+            self.robots.push(Robot { pos: GridPos::of(val(1), val(2)), velocity: GridPos::of(val(3), val(4)) });
+        } else {
+            panic!("Unknown line: {line}");
+        }
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        debug!("{} robots in a {}x{} room", self.robots.len(), self.width, self.height);
+        let part1 = self.safety_factor(SECONDS);
+        info!("[1] Safety factor after {SECONDS} seconds: {part1}");
+
+        let tree = self.most_bunched(self.width, |pos| pos.col)
+            .zip(self.most_bunched(self.height, |pos| pos.row))
+            .and_then(|(tx, ty)| crt(&[(tx, self.width), (ty, self.height)]))
+            .map(|(t, _)| t);
+        let part2 = match tree {
+            Some(t) => {
+                info!("[2] Christmas tree after {t} seconds");
+                self.draw(t);
+                t.to_string()
+            }
+            None => {
+                info!("[2] No Christmas tree in this room");
+                String::from("-")
+            }
+        };
+        Some((part1.to_string(), part2))
+    }
+}
@@ -0,0 +1,184 @@
+// https://adventofcode.com/2024/day/15
+// a push is a recursive check: the robot (or a box) can move if the cell ahead is free, or holds a
+// box that can move. Wide boxes moving up or down push with both halves, so the boxes ahead form a
+// tree: first the whole tree is checked, then moved, leaves first. Sideways a wide box is just
+// two cells in a row, same as the narrow ones.
+// With --debug the first frames are logged, to check the pushes step by step.
+
+use std::fmt::{Display, Formatter};
+use log::{debug, info};
+use adventofcode::grid::{GridPos, MOVE_D, MOVE_L, MOVE_R, MOVE_U};
+use adventofcode::Solver;
+
+pub struct Solution {
+    map: Vec<String>,
+    moves: Vec<GridPos>,
+}
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            map: Vec::new(),
+            moves: Vec::new(),
+        }
+    }
+
+    fn run(&self, mut warehouse: Warehouse) -> i64 {
+        debug!("Initial state:\n{warehouse}");
+        for (i, dir) in self.moves.iter().enumerate() {
+            warehouse.move_robot(dir);
+            if i < DEBUG_FRAMES {
+                debug!("Move {} {}:\n{warehouse}", i + 1, dir);
+            }
+        }
+        debug!("Final state:\n{warehouse}");
+        warehouse.gps()
+    }
+}
+
+/// frames to log (with --debug) at the start of the moves
+const DEBUG_FRAMES: usize = 10;
+const ROBOT: u8 = b'@';
+const WALL: u8 = b'#';
+const EMPTY: u8 = b'.';
+const BOX: u8 = b'O';
+const BOX_LEFT: u8 = b'[';
+const BOX_RIGHT: u8 = b']';
+
+struct Warehouse {
+    cells: Vec<Vec<u8>>,
+    robot: GridPos,
+}
+
+impl Warehouse {
+    fn new(map: &[String], wide: bool) -> Warehouse {
+        let cells: Vec<Vec<u8>> = map.iter()
+            .map(|line| if wide {
+                line.bytes().flat_map(|ch| match ch {
+                    BOX => [BOX_LEFT, BOX_RIGHT],
+                    ROBOT => [ROBOT, EMPTY],
+                    _ => [ch, ch],
+                }).collect()
+            } else {
+                line.bytes().collect()
+            })
+            .collect();
+        let robot = cells.iter().enumerate()
+            .find_map(|(row, line)| line.iter().position(|ch| *ch == ROBOT).map(|col| GridPos::of(col as i64, row as i64)))
+            .expect("No robot in the warehouse");
+        Warehouse { cells, robot }
+    }
+
+    fn at(&self, pos: &GridPos) -> u8 {
+        self.cells[pos.row as usize][pos.col as usize]
+    }
+
+    fn set(&mut self, pos: &GridPos, ch: u8) {
+        self.cells[pos.row as usize][pos.col as usize] = ch;
+    }
+
+    /// whether what is at pos can move in dir (pushing whatever is ahead)
+    fn can_move(&self, pos: &GridPos, dir: &GridPos) -> bool {
+        let next = pos.add(dir);
+        let vertical = dir.row != 0;
+        match self.at(&next) {
+            WALL => false,
+            EMPTY => true,
+            BOX_LEFT if vertical => self.can_move(&next, dir) && self.can_move(&next.add(&MOVE_R), dir),
+            BOX_RIGHT if vertical => self.can_move(&next, dir) && self.can_move(&next.add(&MOVE_L), dir),
+            _ => self.can_move(&next, dir),
+        }
+    }
+
+    /// moves what is at pos in dir, after moving whatever is ahead; can_move must be checked first
+    fn push(&mut self, pos: &GridPos, dir: &GridPos) {
+        let next = pos.add(dir);
+        let vertical = dir.row != 0;
+        match self.at(&next) {
+            EMPTY => {}
+            BOX_LEFT if vertical => {
+                self.push(&next, dir);
+                self.push(&next.add(&MOVE_R), dir);
+            }
+            BOX_RIGHT if vertical => {
+                self.push(&next, dir);
+                self.push(&next.add(&MOVE_L), dir);
+            }
+            _ => self.push(&next, dir),
+        }
+        self.set(&next, self.at(pos));
+        self.set(pos, EMPTY);
+    }
+
+    fn move_robot(&mut self, dir: &GridPos) {
+        if self.can_move(&self.robot, dir) {
+            let robot = self.robot.clone();
+            self.push(&robot, dir);
+            self.robot = robot.add(dir);
+        }
+    }
+
+    fn gps(&self) -> i64 {
+        self.cells.iter().enumerate()
+            .flat_map(|(row, line)| line.iter().enumerate()
+                .filter(|(_, ch)| **ch == BOX || **ch == BOX_LEFT)
+                .map(move |(col, _)| 100 * row as i64 + col as i64))
+            .sum()
+    }
+}
+
+impl Display for Warehouse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for line in &self.cells {
+            writeln!(f, "{}", String::from_utf8_lossy(line))?;
+        }
+        Ok(())
+    }
+}
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        if line.starts_with(WALL as char) {
+            self.map.push(String::from(line));
+            return;
+        }
+        // rows grow downwards
+        self.moves.extend(line.chars().map(|ch| match ch {
+            '^' => MOVE_D,
+            'v' => MOVE_U,
+            '<' => MOVE_L,
+            '>' => MOVE_R,
+            _ => panic!("Invalid move: {ch}"),
+        }));
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        debug!("Warehouse {}x{}, {} moves", self.map[0].len(), self.map.len(), self.moves.len());
+        let part1 = self.run(Warehouse::new(&self.map, false));
+        info!("[1] Sum of GPS coordinates: {part1}");
+        let part2 = self.run(Warehouse::new(&self.map, true));
+        info!("[2] Sum of GPS coordinates in the wide warehouse: {part2}");
+        Some((part1.to_string(), part2.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wide_vertical_push() {
+        let map: Vec<String> = ["#######", "#...#.#", "#.....#", "#..OO@#", "#..O..#", "#.....#", "#######"]
+            .iter().map(|l| String::from(*l)).collect();
+        let mut warehouse = Warehouse::new(&map, true);
+        for dir in [MOVE_L, MOVE_U, MOVE_U, MOVE_L, MOVE_L, MOVE_D, MOVE_D] {
+            warehouse.move_robot(&dir);
+        }
+        assert_eq!(warehouse.to_string(), ["##############", "##......##..##", "##...[][]...##",
+            "##....[]....##", "##.....@....##", "##..........##", "##############", ""].join("\n"));
+        assert_eq!(warehouse.gps(), 205 + 207 + 306);
+    }
+}
@@ -0,0 +1,192 @@
+// https://adventofcode.com/2024/day/16
+// Dijkstra where the state is (tile, facing): stepping forward costs 1, turning costs 1000. When a
+// state is reached again at the same best cost, the new predecessor is kept too, so walking the
+// predecessors back from the best end states visits every tile of every best path.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use log::{debug, info};
+use adventofcode::grid::{GridPos, MOVE_D, MOVE_L, MOVE_R, MOVE_U};
+use adventofcode::Solver;
+
+pub struct Solution {
+    walls: Vec<bool>,
+    width: usize,
+    height: usize,
+    start: usize,
+    end: usize,
+}
+
+impl Solution {
+    pub(crate) fn new() -> Solution {
+        Solution {
+            walls: Vec::new(),
+            width: 0,
+            height: 0,
+            start: 0,
+            end: 0,
+        }
+    }
+
+    /// states reachable from a state, with their cost
+    fn next_states(&self, state: usize) -> Vec<(usize, u64)> {
+        let (idx, dir) = (state / DIRS.len(), state % DIRS.len());
+        let mut next = vec![
+            (idx * DIRS.len() + (dir + 1) % DIRS.len(), TURN_COST),
+            (idx * DIRS.len() + (dir + DIRS.len() - 1) % DIRS.len(), TURN_COST),
+        ];
+        let forward = GridPos::from_linear(idx, self.width).add(&DIRS[dir]).to_linear(self.width, self.height);
+        if let Some(forward) = forward.filter(|f| !self.walls[*f]) {
+            next.push((forward * DIRS.len() + dir, STEP_COST));
+        }
+        next
+    }
+
+    /// lowest cost to each state, and all the predecessors of each state on its lowest cost paths
+    fn dijkstra(&self) -> (Vec<u64>, Vec<Vec<usize>>) {
+        let states = self.walls.len() * DIRS.len();
+        let mut costs = vec![u64::MAX; states];
+        let mut predecessors = vec![Vec::new(); states];
+        let start = self.start * DIRS.len() + START_DIR;
+        costs[start] = 0;
+        let mut queue = BinaryHeap::from([Reverse((0, start))]);
+        while let Some(Reverse((cost, state))) = queue.pop() {
+            if cost > costs[state] {
+                continue;
+            }
+            for (next, step) in self.next_states(state) {
+                let next_cost = cost + step;
+                if next_cost < costs[next] {
+                    costs[next] = next_cost;
+                    predecessors[next] = vec![state];
+                    queue.push(Reverse((next_cost, next)));
+                } else if next_cost == costs[next] {
+                    predecessors[next].push(state);
+                }
+            }
+        }
+        (costs, predecessors)
+    }
+
+    /// states on the end tile, whatever the facing
+    fn end_states(&self) -> Vec<usize> {
+        (0..DIRS.len()).map(|dir| self.end * DIRS.len() + dir).collect()
+    }
+
+    fn lowest_cost(&self, costs: &[u64]) -> u64 {
+        self.end_states().iter().map(|s| costs[*s]).min().unwrap()
+    }
+
+    /// tiles on any of the lowest cost paths, walking back from all the best end states
+    fn best_path_tiles(&self, costs: &[u64], predecessors: &[Vec<usize>]) -> usize {
+        let lowest = self.lowest_cost(costs);
+        let mut seen = vec![false; costs.len()];
+        let mut stack: Vec<usize> = self.end_states().into_iter().filter(|s| costs[*s] == lowest).collect();
+        stack.iter().for_each(|s| seen[*s] = true);
+        while let Some(state) = stack.pop() {
+            for prev in &predecessors[state] {
+                if !seen[*prev] {
+                    seen[*prev] = true;
+                    stack.push(*prev);
+                }
+            }
+        }
+        (0..self.walls.len())
+            .filter(|idx| (0..DIRS.len()).any(|dir| seen[idx * DIRS.len() + dir]))
+            .count()
+    }
+}
+
+/// clockwise, starting east (rows grow downwards)
+const DIRS: [GridPos; 4] = [MOVE_R, MOVE_U, MOVE_L, MOVE_D];
+const START_DIR: usize = 0;
+const STEP_COST: u64 = 1;
+const TURN_COST: u64 = 1000;
+
+impl Solver for Solution {
+    fn parse(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        if self.width == 0 {
+            self.width = line.len();
+        }
+        assert_eq!(self.width, line.len(), "Maze is not a rectangle?");
+        for (col, ch) in line.chars().enumerate() {
+            let idx = self.height * self.width + col;
+            match ch {
+                'S' => self.start = idx,
+                'E' => self.end = idx,
+                _ => {}
+            }
+            self.walls.push(ch == '#');
+        }
+        self.height += 1;
+    }
+
+    fn solve(&mut self) -> Option<(String, String)> {
+        debug!("Maze {}x{}, from {} to {}", self.width, self.height,
+            GridPos::from_linear(self.start, self.width), GridPos::from_linear(self.end, self.width));
+        let (costs, predecessors) = self.dijkstra();
+        let part1 = self.lowest_cost(&costs);
+        info!("[1] Lowest score: {part1}");
+        let part2 = self.best_path_tiles(&costs, &predecessors);
+        info!("[2] Tiles on the best paths: {part2}");
+        Some((part1.to_string(), part2.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// lowest cost and tiles on the best paths
+    fn best_paths(lines: &[&str]) -> (u64, usize) {
+        let mut maze = Solution::new();
+        lines.iter().for_each(|l| maze.parse(l));
+        let (costs, predecessors) = maze.dijkstra();
+        (maze.lowest_cost(&costs), maze.best_path_tiles(&costs, &predecessors))
+    }
+
+    #[test]
+    fn test_first_example() {
+        let maze = ["###############",
+            "#.......#....E#",
+            "#.#.###.#.###.#",
+            "#.....#.#...#.#",
+            "#.###.#####.#.#",
+            "#.#.#.......#.#",
+            "#.#.#####.###.#",
+            "#...........#.#",
+            "###.#.#####.#.#",
+            "#...#.....#.#.#",
+            "#.#.#.###.#.#.#",
+            "#.....#...#.#.#",
+            "#.###.#.#.#.#.#",
+            "#S..#.....#...#",
+            "###############"];
+        assert_eq!(best_paths(&maze), (7036, 45));
+    }
+
+    #[test]
+    fn test_second_example() {
+        let maze = ["#################",
+            "#...#...#...#..E#",
+            "#.#.#.#.#.#.#.#.#",
+            "#.#.#.#...#...#.#",
+            "#.#.#.#.###.#.#.#",
+            "#...#.#.#.....#.#",
+            "#.#.#.#.#.#####.#",
+            "#.#...#.#.#.....#",
+            "#.#.#####.#.###.#",
+            "#.#.#.......#...#",
+            "#.#.###.#####.###",
+            "#.#.#...#.....#.#",
+            "#.#.#.#####.###.#",
+            "#.#.#.........#.#",
+            "#.#.#.#########.#",
+            "#S#.............#",
+            "#################"];
+        assert_eq!(best_paths(&maze), (11048, 64));
+    }
+}